@@ -0,0 +1,328 @@
+//! Instruction builders and account decoding for the uber_mm program, the account and
+//! instruction types are shared with the program through its `cpi` feature.
+
+use anchor_lang::{
+    __private::bytemuck,
+    prelude::*,
+    solana_program::{instruction::Instruction, system_program},
+    Discriminator, InstructionData, ZeroCopy,
+};
+use phoenix::program::MarketHeader;
+
+pub use uber_mm::{
+    self, config::MarketConfig, get_trader_authority_address, history::StrategyHistory,
+    PhoenixStrategyState, PriceImprovementBehavior,
+};
+
+/// Strategy PDA of an owner on a Phoenix market
+pub fn get_phoenix_strategy_address(owner: &Pubkey, market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"phoenix".as_ref(), owner.as_ref(), market.as_ref()],
+        &uber_mm::id(),
+    )
+    .0
+}
+
+/// Global config holding the registry admin
+pub fn get_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config".as_ref()], &uber_mm::id()).0
+}
+
+/// Registry entry holding the oracles of a Phoenix market
+pub fn get_market_config_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"market".as_ref(), market.as_ref()], &uber_mm::id()).0
+}
+
+pub fn get_strategy_history_address(phoenix_strategy: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"history".as_ref(), phoenix_strategy.as_ref()],
+        &uber_mm::id(),
+    )
+    .0
+}
+
+/// Creates a strategy traded by the user's wallet, the market has to be registered
+pub fn create_initialize_instruction(
+    user: &Pubkey,
+    market: &Pubkey,
+    quote_edge_in_bps: u64,
+    quote_size_in_quote_atoms: u64,
+    price_improvement_behavior: PriceImprovementBehavior,
+    post_only: bool,
+    guardian: Option<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: uber_mm::id(),
+        accounts: uber_mm::accounts::Initialize {
+            phoenix_strategy: get_phoenix_strategy_address(user, market),
+            user: *user,
+            market: *market,
+            market_config: get_market_config_address(market),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: uber_mm::instruction::Initialize {
+            quote_edge_in_bps,
+            quote_size_in_quote_atoms,
+            price_improvement_behavior: price_improvement_behavior.to_u8(),
+            post_only,
+            guardian,
+        }
+        .data(),
+    }
+}
+
+/// Updates the quotes of the owner's strategy. The authority is the owner or the operator,
+/// a wallet trader has to sign as well. The oracles are the ones registered for the market
+/// and the history account is appended if the strategy has one.
+#[allow(clippy::too_many_arguments)]
+pub fn create_update_quotes_instruction(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    trader: &Pubkey,
+    market: &Pubkey,
+    header: &MarketHeader,
+    quote_account: &Pubkey,
+    base_account: &Pubkey,
+    market_config: &MarketConfig,
+    with_history: bool,
+    fair_price_in_quote_atoms_per_raw_base_unit: Option<u64>,
+) -> Instruction {
+    let phoenix_strategy = get_phoenix_strategy_address(owner, market);
+    let mut accounts = uber_mm::accounts::UpdateQuotes {
+        phoenix_strategy,
+        authority: *authority,
+        trader: *trader,
+        phoenix_program: phoenix::id(),
+        log_authority: phoenix::phoenix_log_authority::id(),
+        market: *market,
+        market_config: get_market_config_address(market),
+        seat: phoenix::program::get_seat_address(market, trader).0,
+        quote_account: *quote_account,
+        base_account: *base_account,
+        quote_vault: header.quote_params.vault_key,
+        base_vault: header.base_params.vault_key,
+        token_program: anchor_spl::token::ID,
+    }
+    .to_account_metas(None);
+    // The program derived trader authority signs through the program
+    if *trader != uber_mm::get_trader_authority_address(&phoenix_strategy).0 {
+        accounts
+            .iter_mut()
+            .filter(|meta| meta.pubkey == *trader)
+            .for_each(|meta| meta.is_signer = true);
+    }
+    accounts.push(AccountMeta::new_readonly(market_config.base_oracle, false));
+    accounts.push(AccountMeta::new_readonly(market_config.quote_oracle, false));
+    if with_history {
+        accounts.push(AccountMeta::new(
+            get_strategy_history_address(&phoenix_strategy),
+            false,
+        ));
+    }
+    Instruction {
+        program_id: uber_mm::id(),
+        accounts,
+        data: uber_mm::instruction::UpdateQuotes {
+            fair_price_in_quote_atoms_per_raw_base_unit,
+        }
+        .data(),
+    }
+}
+
+pub fn decode_phoenix_strategy(data: &[u8]) -> Result<PhoenixStrategyState> {
+    decode_zero_copy_account(data)
+}
+
+pub fn decode_market_config(data: &[u8]) -> Result<MarketConfig> {
+    decode_zero_copy_account(data)
+}
+
+/// Checks the discriminator and copies the account out of the data, which does not have
+/// to be aligned
+fn decode_zero_copy_account<T: ZeroCopy + Discriminator>(data: &[u8]) -> Result<T> {
+    let size = 8 + std::mem::size_of::<T>();
+    require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+    require!(
+        data[..8] == T::discriminator(),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(data.len() >= size, ErrorCode::AccountDidNotDeserialize);
+    Ok(bytemuck::pod_read_unaligned(&data[8..size]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phoenix::quantities::{
+        BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot, WrapperU64,
+    };
+    use uber_mm::config::OracleType;
+
+    fn test_header() -> MarketHeader {
+        let mut header = MarketHeader::new(
+            bytemuck::Zeroable::zeroed(),
+            bytemuck::Zeroable::zeroed(),
+            BaseAtomsPerBaseLot::new(1000),
+            bytemuck::Zeroable::zeroed(),
+            QuoteAtomsPerQuoteLot::new(1),
+            QuoteAtomsPerBaseUnitPerTick::new(100),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            1,
+        );
+        header.quote_params.vault_key = Pubkey::new_unique();
+        header.base_params.vault_key = Pubkey::new_unique();
+        header
+    }
+
+    fn test_market_config(market: &Pubkey) -> MarketConfig {
+        MarketConfig::new(
+            *market,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            OracleType::Pyth,
+        )
+    }
+
+    #[test]
+    fn addresses_use_the_program_seeds() {
+        let owner = Pubkey::new_unique();
+        let market = Pubkey::new_unique();
+        let phoenix_strategy = get_phoenix_strategy_address(&owner, &market);
+        assert_eq!(
+            phoenix_strategy,
+            Pubkey::find_program_address(
+                &[b"phoenix", owner.as_ref(), market.as_ref()],
+                &uber_mm::id()
+            )
+            .0
+        );
+        assert_eq!(
+            get_trader_authority_address(&phoenix_strategy).0,
+            Pubkey::find_program_address(&[b"trader", phoenix_strategy.as_ref()], &uber_mm::id()).0
+        );
+        assert_eq!(
+            get_market_config_address(&market),
+            Pubkey::find_program_address(&[b"market", market.as_ref()], &uber_mm::id()).0
+        );
+        assert_eq!(
+            get_config_address(),
+            Pubkey::find_program_address(&[b"config"], &uber_mm::id()).0
+        );
+        assert_eq!(
+            get_strategy_history_address(&phoenix_strategy),
+            Pubkey::find_program_address(&[b"history", phoenix_strategy.as_ref()], &uber_mm::id())
+                .0
+        );
+    }
+
+    #[test]
+    fn update_quotes_account_order() {
+        let owner = Pubkey::new_unique();
+        let market = Pubkey::new_unique();
+        let quote_account = Pubkey::new_unique();
+        let base_account = Pubkey::new_unique();
+        let header = test_header();
+        let market_config = test_market_config(&market);
+        let phoenix_strategy = get_phoenix_strategy_address(&owner, &market);
+
+        for with_history in [false, true] {
+            let instruction = create_update_quotes_instruction(
+                &owner,
+                &owner,
+                &owner,
+                &market,
+                &header,
+                &quote_account,
+                &base_account,
+                &market_config,
+                with_history,
+                None,
+            );
+            let mut expected = vec![
+                AccountMeta::new(phoenix_strategy, false),
+                AccountMeta::new_readonly(owner, true),
+                AccountMeta::new_readonly(owner, true),
+                AccountMeta::new_readonly(phoenix::id(), false),
+                AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+                AccountMeta::new(market, false),
+                AccountMeta::new_readonly(get_market_config_address(&market), false),
+                AccountMeta::new_readonly(
+                    phoenix::program::get_seat_address(&market, &owner).0,
+                    false,
+                ),
+                AccountMeta::new(quote_account, false),
+                AccountMeta::new(base_account, false),
+                AccountMeta::new(header.quote_params.vault_key, false),
+                AccountMeta::new(header.base_params.vault_key, false),
+                AccountMeta::new_readonly(anchor_spl::token::ID, false),
+                AccountMeta::new_readonly(market_config.base_oracle, false),
+                AccountMeta::new_readonly(market_config.quote_oracle, false),
+            ];
+            if with_history {
+                expected.push(AccountMeta::new(
+                    get_strategy_history_address(&phoenix_strategy),
+                    false,
+                ));
+            }
+            assert_eq!(instruction.program_id, uber_mm::id());
+            assert_eq!(instruction.accounts, expected);
+        }
+    }
+
+    #[test]
+    fn program_owned_trader_does_not_sign() {
+        let owner = Pubkey::new_unique();
+        let market = Pubkey::new_unique();
+        let trader = get_trader_authority_address(&get_phoenix_strategy_address(&owner, &market)).0;
+        let instruction = create_update_quotes_instruction(
+            &owner,
+            &owner,
+            &trader,
+            &market,
+            &test_header(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &test_market_config(&market),
+            false,
+            None,
+        );
+        assert_eq!(
+            instruction.accounts[2],
+            AccountMeta::new_readonly(trader, false)
+        );
+    }
+
+    #[test]
+    fn phoenix_strategy_round_trip() {
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        strategy.trader = Pubkey::new_unique();
+        strategy.market = Pubkey::new_unique();
+        strategy.quote_edge_in_bps = 25;
+        strategy.net_position_in_base_lots = -42;
+        let mut data = PhoenixStrategyState::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&strategy));
+        // The copy works from any offset, account data is not aligned for the struct
+        let mut unaligned = vec![0];
+        unaligned.extend_from_slice(&data);
+
+        let decoded = decode_phoenix_strategy(&unaligned[1..]).unwrap();
+        assert_eq!(decoded.trader, strategy.trader);
+        assert_eq!(decoded.market, strategy.market);
+        assert_eq!(decoded.quote_edge_in_bps, 25);
+        assert_eq!(decoded.net_position_in_base_lots, -42);
+        assert_eq!(bytemuck::bytes_of(&decoded), bytemuck::bytes_of(&strategy));
+
+        data[0] ^= 1;
+        assert_eq!(
+            decode_phoenix_strategy(&data).err(),
+            Some(error!(ErrorCode::AccountDiscriminatorMismatch))
+        );
+        assert_eq!(
+            decode_phoenix_strategy(&unaligned[1..9]).err(),
+            Some(error!(ErrorCode::AccountDidNotDeserialize))
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::*;
+
+/// Program wide settings, the admin manages the market registry
+#[account(zero_copy)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum OracleType {
+    Pyth,
+}
+
+u8_enum!(OracleType, StrategyError::InvalidOracleType, { Pyth = 0 });
+
+/// Registry entry of a Phoenix market, strategies can only be created and quoted on
+/// enabled markets and always use the registered oracles
+#[account(zero_copy)]
+pub struct MarketConfig {
+    pub market: Pubkey,
+    pub base_oracle: Pubkey,
+    pub quote_oracle: Pubkey,
+    pub oracle_type: u8,
+    pub enabled: bool,
+    padding: [u8; 6],
+}
+
+impl MarketConfig {
+    pub fn new(
+        market: Pubkey,
+        base_oracle: Pubkey,
+        quote_oracle: Pubkey,
+        oracle_type: OracleType,
+    ) -> Self {
+        MarketConfig {
+            market,
+            base_oracle,
+            quote_oracle,
+            oracle_type: oracle_type.to_u8(),
+            enabled: true,
+            padding: [0; 6],
+        }
+    }
+
+    /// Loads the base and quote prices scaled by BIG_NUMBER from the registered oracles,
+    /// strategy is only used to report rejected oracles
+    pub fn load_prices(
+        &self,
+        strategy: &Pubkey,
+        base_oracle: &AccountInfo,
+        quote_oracle: &AccountInfo,
+    ) -> Result<(u128, u128)> {
+        let (base_price, quote_price, _) =
+            self.load_prices_with_confidence(strategy, base_oracle, quote_oracle)?;
+        Ok((base_price, quote_price))
+    }
+
+    /// Same as load_prices, followed by the confidence interval of the base price
+    pub fn load_prices_with_confidence(
+        &self,
+        strategy: &Pubkey,
+        base_oracle: &AccountInfo,
+        quote_oracle: &AccountInfo,
+    ) -> Result<(u128, u128, u128)> {
+        require_keys_eq!(
+            base_oracle.key(),
+            self.base_oracle,
+            StrategyError::InvalidOracleAccount
+        );
+        require_keys_eq!(
+            quote_oracle.key(),
+            self.quote_oracle,
+            StrategyError::InvalidOracleAccount
+        );
+        match OracleType::try_from(self.oracle_type)? {
+            OracleType::Pyth => {
+                let (base_price, base_confidence) =
+                    load_scaled_price_and_confidence(base_oracle, strategy)?;
+                Ok((
+                    base_price,
+                    load_scaled_price(quote_oracle, strategy)?,
+                    base_confidence,
+                ))
+            }
+        }
+    }
+}
@@ -3,6 +3,14 @@ pub const PHOENIX_MARKET_DISCRIMINANT: u64 = 8167313896524341111;
 
 pub const BIG_NUMBER: u128 = 1000000000000;
 
+pub const PHOENIX_STRATEGY_VERSION: u8 = 5;
+
+pub const SECONDS_PER_YEAR: u64 = 31536000;
+
+pub const MAX_PORTFOLIO_STRATEGIES: usize = 8;
+
+pub const HISTORY_LENGTH: usize = 64;
+
 #[error_code]
 pub enum StrategyError {
     NoReturnData,
@@ -14,4 +22,36 @@ pub enum StrategyError {
     PythValidSlot,
     PythNegativePrice,
     PythConfidence,
+    StrategyPaused,
+    InvalidPauseAuthority,
+    UpdateTooFrequent,
+    OrderNotionalTooLarge,
+    TooManyCancelReplaceCycles,
+    TraderNotProgramOwned,
+    StrategyNotStale,
+    InvalidStrategyAuthority,
+    InvalidStrategyAccount,
+    StrategyNeedsMigration,
+    InvalidVaultMint,
+    InvalidVaultAmount,
+    InvalidVaultTokenAccount,
+    VaultOrdersResting,
+    WithdrawalQueueDisabled,
+    StrategyHasVault,
+    InvalidVaultFees,
+    PortfolioFull,
+    PortfolioStrategyExists,
+    PortfolioStrategyNotFound,
+    InvalidPortfolioAccounts,
+    InvalidConfigAdmin,
+    MarketDisabled,
+    InvalidOracleAccount,
+    InvalidPriceImprovementBehavior,
+    InvalidSelfTradeBehavior,
+    InvalidOracleType,
+    InvalidHistoryAccount,
+    FairPriceOverrideNotAllowed,
+    VaultNavPerShareOverflow,
+    StrategyHasHistory,
+    UnsupportedStrategyVersion,
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use crate::*;
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum QuoteSide {
+    Bid,
+    Ask,
+}
+
+impl From<Side> for QuoteSide {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Bid => QuoteSide::Bid,
+            Side::Ask => QuoteSide::Ask,
+        }
+    }
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum OracleRejectionReason {
+    StaleSlot,
+    NotTrading,
+    NegativePrice,
+    WideConfidence,
+}
+
+#[event]
+pub struct StrategyInitialized {
+    pub strategy: Pubkey,
+    pub owner: Pubkey,
+    pub trader: Pubkey,
+    pub market: Pubkey,
+}
+
+#[event]
+pub struct StrategyParamsChanged {
+    pub strategy: Pubkey,
+    pub params: StrategyParams,
+}
+
+#[event]
+pub struct RiskParamsChanged {
+    pub strategy: Pubkey,
+    pub params: RiskParams,
+}
+
+#[event]
+pub struct ExecutionParamsChanged {
+    pub strategy: Pubkey,
+    pub params: ExecutionParams,
+}
+
+#[event]
+pub struct OperatorChanged {
+    pub strategy: Pubkey,
+    pub operator: Option<Pubkey>,
+}
+
+#[event]
+pub struct QuotePlaced {
+    pub strategy: Pubkey,
+    pub side: QuoteSide,
+    pub price_in_ticks: u64,
+    pub size_in_base_lots: u64,
+    pub order_sequence_number: u64,
+}
+
+/// Either the listed orders or, if cancelled_all is set, every order of the trader
+#[event]
+pub struct OrdersCancelled {
+    pub strategy: Pubkey,
+    pub order_sequence_numbers: Vec<u64>,
+    pub cancelled_all: bool,
+}
+
+#[event]
+pub struct FillDetected {
+    pub strategy: Pubkey,
+    pub side: QuoteSide,
+    pub price_in_ticks: u64,
+    pub base_lots: u64,
+    pub quote_atoms: u64,
+}
+
+/// Emitted right before the oracle error is returned, so it only shows up in the logs of failed
+/// transactions unless update_portfolio skips the strategy and carries on. The strategy is
+/// Pubkey::default() when the oracles are checked for a market config
+#[event]
+pub struct OracleRejected {
+    pub strategy: Pubkey,
+    pub oracle: Pubkey,
+    pub reason: OracleRejectionReason,
+    pub price: i64,
+    pub confidence: u64,
+    pub valid_slot: u64,
+    pub current_slot: u64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub strategy: Pubkey,
+    pub best_bid_in_ticks: u64,
+    pub best_ask_in_ticks: u64,
+    pub fair_price_in_ticks: u64,
+    pub deviation_in_bps: u64,
+    pub max_book_deviation_in_bps: u64,
+}
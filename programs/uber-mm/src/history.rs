@@ -0,0 +1,40 @@
+use crate::*;
+
+/// What update_quotes saw and did in one call
+#[zero_copy]
+#[derive(Default)]
+pub struct UpdateRecord {
+    pub slot: u64,
+    pub fair_price_in_ticks: u64,
+    /// Confidence interval of the base oracle price, 0 when the fair price was overridden
+    pub oracle_confidence_in_ticks: u64,
+    /// Best prices of other traders
+    pub best_bid_in_ticks: u64,
+    pub best_ask_in_ticks: u64,
+    /// Our resting orders after the update, 0 if there is none
+    pub bid_price_in_ticks: u64,
+    pub bid_size_in_base_lots: u64,
+    pub ask_price_in_ticks: u64,
+    pub ask_size_in_base_lots: u64,
+    /// Trader inventory in the token accounts and the Phoenix seat
+    pub quote_atoms_held: u64,
+    pub base_atoms_held: u64,
+}
+
+/// Ring buffer with the last HISTORY_LENGTH updates of a strategy
+#[account(zero_copy)]
+pub struct StrategyHistory {
+    pub phoenix_strategy: Pubkey,
+    /// Index the next record is written at
+    pub next_index: u64,
+    pub num_records: u64,
+    pub records: [UpdateRecord; HISTORY_LENGTH],
+}
+
+impl StrategyHistory {
+    pub fn push(&mut self, record: UpdateRecord) {
+        self.records[self.next_index as usize] = record;
+        self.next_index = (self.next_index + 1) % HISTORY_LENGTH as u64;
+        self.num_records = (self.num_records + 1).min(HISTORY_LENGTH as u64);
+    }
+}
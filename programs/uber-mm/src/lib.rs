@@ -1,21 +1,63 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token;
 use oracle::*;
 use consts::*;
+use vault::*;
+use portfolio::*;
+use config::*;
+use events::*;
+use history::*;
+
+/// Implements the u8 storage of a strategy enum. Decoding an unknown byte returns the given
+/// error instead of panicking, so a bad stored value can't brick the strategy
+macro_rules! u8_enum {
+    ($name:ident, $error:path, { $($variant:ident = $byte:literal),+ $(,)? }) => {
+        impl $name {
+            pub fn to_u8(&self) -> u8 {
+                match self {
+                    $($name::$variant => $byte,)+
+                }
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = anchor_lang::error::Error;
+
+            fn try_from(byte: u8) -> Result<Self> {
+                match byte {
+                    $($byte => Ok($name::$variant),)+
+                    _ => Err(error!($error)),
+                }
+            }
+        }
+    };
+}
+
 pub mod oracle;
 pub mod consts;
+pub mod vault;
+pub mod portfolio;
+pub mod config;
+pub mod events;
+pub mod history;
 use anchor_lang::{
     __private::bytemuck::{self},
-    solana_program::program::{get_return_data, invoke},
+    system_program, Discriminator,
+    solana_program::program::{get_return_data, invoke_signed},
 };
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use phoenix::program::{
-    new_order::{CondensedOrder, MultipleOrderPacket},
+    deposit::DepositParams,
+    new_order::{CondensedOrder, FailedMultipleLimitOrderBehavior, MultipleOrderPacket},
+    withdraw::WithdrawParams,
     CancelMultipleOrdersByIdParams, CancelOrderParams, MarketHeader,
 };
 use phoenix::{
-    quantities::WrapperU64,
+    quantities::{BaseLots, Ticks, WrapperU64},
     state::{
         markets::{FIFOOrderId, FIFORestingOrder, Market},
-        OrderPacket, Side,
+        OrderPacket, Side, TraderState,
     },
 };
 
@@ -30,6 +72,39 @@ impl anchor_lang::Id for PhoenixV1 {
     }
 }
 
+#[derive(Clone)]
+pub struct PhoenixSeatManager;
+
+impl anchor_lang::Id for PhoenixSeatManager {
+    fn id() -> Pubkey {
+        phoenix_seat_manager::id()
+    }
+}
+
+
+/// Program derived key that can act as the Phoenix trader of a strategy,
+/// letting the program sign Phoenix instructions on its behalf
+pub fn get_trader_authority_address(phoenix_strategy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trader".as_ref(), phoenix_strategy.as_ref()], &crate::id())
+}
+
+/// Returns None if the trader signed the transaction itself, or the trader authority bump
+/// if the program has to sign the Phoenix instructions on its behalf
+fn get_trader_authority_bump(
+    phoenix_strategy: &Pubkey,
+    trader: &AccountInfo,
+) -> Result<Option<u8>> {
+    if trader.is_signer {
+        return Ok(None);
+    }
+    let (trader_authority, bump) = get_trader_authority_address(phoenix_strategy);
+    require_keys_eq!(
+        trader_authority,
+        trader.key(),
+        StrategyError::TraderNotProgramOwned
+    );
+    Ok(Some(bump))
+}
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy)]
 struct DeserializedFIFOOrderId {
@@ -39,9 +114,7 @@ struct DeserializedFIFOOrderId {
 
 fn parse_order_ids_from_return_data(order_ids: &mut Vec<FIFOOrderId>) -> Result<()> {
     if let Some((program_id, orders_data)) = get_return_data() {
-        msg!("Found return data");
         if program_id == phoenix::id() && !orders_data.is_empty() {
-            msg!("Found orders in return data");
             Vec::<DeserializedFIFOOrderId>::try_from_slice(&orders_data)?
                 .into_iter()
                 .for_each(|o| {
@@ -50,8 +123,6 @@ fn parse_order_ids_from_return_data(order_ids: &mut Vec<FIFOOrderId>) -> Result<
                         o.order_sequence_number,
                     ))
                 });
-        } else {
-            msg!("No orders in return data");
         }
     }
     Ok(())
@@ -76,6 +147,273 @@ fn load_header(info: &AccountInfo) -> Result<MarketHeader> {
     Ok(*header)
 }
 
+fn load_trader_state(
+    market_account: &AccountInfo,
+    header: &MarketHeader,
+    trader: &Pubkey,
+) -> Result<TraderState> {
+    let market_data = market_account.data.borrow();
+    let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
+    let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
+        .map_err(|_| {
+            msg!("Failed to deserialize market");
+            StrategyError::FailedToDeserializePhoenixMarket
+        })?
+        .inner;
+    Ok(market.get_trader_state(trader).copied().unwrap_or_default())
+}
+
+/// Quote and base atoms held by the trader in its token accounts and Phoenix seat
+fn get_trader_holdings(
+    market_account: &AccountInfo,
+    header: &MarketHeader,
+    trader: &Pubkey,
+    quote_account: &AccountInfo,
+    base_account: &AccountInfo,
+) -> Result<(u64, u64)> {
+    let trader_state = load_trader_state(market_account, header, trader)?;
+    let quote_atoms = (trader_state.quote_lots_locked + trader_state.quote_lots_free).as_u64()
+        * header.get_quote_lot_size().as_u64()
+        + token::accessor::amount(quote_account)?;
+    let base_atoms = (trader_state.base_lots_locked + trader_state.base_lots_free).as_u64()
+        * header.get_base_lot_size().as_u64()
+        + token::accessor::amount(base_account)?;
+    Ok((quote_atoms, base_atoms))
+}
+
+/// Cancels every order the trader has on the market, releasing the funds to the seat
+fn cancel_all_orders<'info>(
+    phoenix_program: &AccountInfo<'info>,
+    log_authority: &AccountInfo<'info>,
+    trader: &AccountInfo<'info>,
+    market: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_signed(
+        &phoenix::program::create_cancel_all_order_with_free_funds_instruction(
+            market.key,
+            trader.key,
+        ),
+        &[
+            phoenix_program.clone(),
+            log_authority.clone(),
+            trader.clone(),
+            market.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Records what the tracked orders filled since they were placed, this runs before we cancel
+/// anything and every tracked order that is not kept has to be cleared afterwards. Maker fills
+/// credit their proceeds to the trader's free lots while a cancel releases the lots of the
+/// order's own side, so an order that shrank or left the book only counts as filled up to the
+/// proceeds credited since the last update
+fn record_fills(
+    strategy_key: &Pubkey,
+    phoenix_strategy: &mut PhoenixStrategyState,
+    market: &dyn Market<Pubkey, FIFOOrderId, FIFORestingOrder, OrderPacket>,
+    header: &MarketHeader,
+) {
+    let base_lots_per_base_unit = market.get_base_lots_per_base_unit().as_u64();
+    let quote_lot_size = header.get_quote_lot_size().as_u64();
+    let tick_size_in_quote_atoms = header
+        .get_tick_size_in_quote_atoms_per_base_unit()
+        .as_u128();
+    let (quote_lots_free, base_lots_free) = get_trader_free_lots(market, &phoenix_strategy.trader);
+    for (side, price_in_ticks, order_sequence_number, initial_size_in_base_lots) in [
+        (
+            Side::Bid,
+            phoenix_strategy.bid_price_in_ticks,
+            phoenix_strategy.bid_order_sequence_number,
+            phoenix_strategy.initial_bid_size_in_base_lots,
+        ),
+        (
+            Side::Ask,
+            phoenix_strategy.ask_price_in_ticks,
+            phoenix_strategy.ask_order_sequence_number,
+            phoenix_strategy.initial_ask_size_in_base_lots,
+        ),
+    ] {
+        if initial_size_in_base_lots == 0 {
+            continue;
+        }
+        let order_id = FIFOOrderId::new_from_untyped(price_in_ticks, order_sequence_number);
+        let resting_base_lots = market
+            .get_book(side)
+            .get(&order_id)
+            .map(|order| order.num_base_lots.as_u64())
+            .unwrap_or(0);
+        let credited_base_lots = match side {
+            Side::Bid => {
+                (base_lots_free as i128 - phoenix_strategy.base_lots_free_baseline as i128).max(0)
+            }
+            Side::Ask => {
+                let credited_quote_lots = (quote_lots_free as i128
+                    - phoenix_strategy.quote_lots_free_baseline as i128)
+                    .max(0);
+                credited_quote_lots * quote_lot_size as i128 * base_lots_per_base_unit as i128
+                    / (price_in_ticks as i128 * tick_size_in_quote_atoms as i128)
+            }
+        };
+        let filled_base_lots = initial_size_in_base_lots
+            .saturating_sub(resting_base_lots)
+            .min(credited_base_lots.min(u64::MAX as i128) as u64);
+        if filled_base_lots == 0 {
+            continue;
+        }
+        let quote_atoms = get_order_notional_in_quote_atoms(
+            price_in_ticks,
+            filled_base_lots,
+            header,
+            base_lots_per_base_unit,
+        );
+        // The credited proceeds are used up by this fill
+        match side {
+            Side::Bid => phoenix_strategy.base_lots_free_baseline += filled_base_lots as i64,
+            Side::Ask => {
+                phoenix_strategy.quote_lots_free_baseline += (quote_atoms / quote_lot_size) as i64
+            }
+        }
+        phoenix_strategy.record_fill(side, price_in_ticks, filled_base_lots, quote_atoms);
+        phoenix_strategy.update_cost_basis(
+            side,
+            filled_base_lots,
+            quote_atoms,
+            header,
+            base_lots_per_base_unit,
+        );
+        emit!(FillDetected {
+            strategy: *strategy_key,
+            side: side.into(),
+            price_in_ticks,
+            base_lots: filled_base_lots,
+            quote_atoms,
+        });
+    }
+}
+
+/// Records what the limit orders took from the book when they were placed, from the change
+/// of the trader's quote and base holdings around the placement so taker fees are included
+fn record_placement_fill(
+    strategy_key: &Pubkey,
+    phoenix_strategy: &mut PhoenixStrategyState,
+    header: &MarketHeader,
+    base_lots_per_base_unit: u64,
+    (quote_atoms_before, base_atoms_before): (u64, u64),
+    (quote_atoms_after, base_atoms_after): (u64, u64),
+) {
+    let (side, base_atoms, quote_atoms) = match base_atoms_after.cmp(&base_atoms_before) {
+        Ordering::Greater => (
+            Side::Bid,
+            base_atoms_after - base_atoms_before,
+            quote_atoms_before.saturating_sub(quote_atoms_after),
+        ),
+        Ordering::Less => (
+            Side::Ask,
+            base_atoms_before - base_atoms_after,
+            quote_atoms_after.saturating_sub(quote_atoms_before),
+        ),
+        Ordering::Equal => return,
+    };
+    let base_lots = base_atoms / header.get_base_lot_size().as_u64();
+    if base_lots == 0 {
+        return;
+    }
+    // Average price the orders took at, after fees
+    let tick_size_in_quote_atoms = header
+        .get_tick_size_in_quote_atoms_per_base_unit()
+        .as_u128();
+    let price_in_ticks = (quote_atoms as u128 * base_lots_per_base_unit as u128
+        / (base_lots as u128 * tick_size_in_quote_atoms)) as u64;
+    phoenix_strategy.record_fill(side, price_in_ticks, base_lots, quote_atoms);
+    phoenix_strategy.update_cost_basis(
+        side,
+        base_lots,
+        quote_atoms,
+        header,
+        base_lots_per_base_unit,
+    );
+    emit!(FillDetected {
+        strategy: *strategy_key,
+        side: side.into(),
+        price_in_ticks,
+        base_lots,
+        quote_atoms,
+    });
+}
+
+fn get_trader_free_lots(
+    market: &dyn Market<Pubkey, FIFOOrderId, FIFORestingOrder, OrderPacket>,
+    trader: &Pubkey,
+) -> (u64, u64) {
+    market
+        .get_trader_state(trader)
+        .map(|state| {
+            (
+                state.quote_lots_free.as_u64(),
+                state.base_lots_free.as_u64(),
+            )
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Records the fills of the tracked orders before all of them get cancelled
+fn record_fills_before_cancel(
+    strategy_key: &Pubkey,
+    phoenix_strategy: &mut PhoenixStrategyState,
+    market_account: &AccountInfo,
+) -> Result<()> {
+    let header = load_header(market_account)?;
+    let market_data = market_account.data.borrow();
+    let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
+    let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
+        .map_err(|_| {
+            msg!("Failed to deserialize market");
+            StrategyError::FailedToDeserializePhoenixMarket
+        })?
+        .inner;
+    record_fills(strategy_key, phoenix_strategy, market, &header);
+    Ok(())
+}
+
+/// Requests and approves the trader's seat through the Phoenix seat manager,
+/// the trader pays for the seat and the seat manager deposit
+#[allow(clippy::too_many_arguments)]
+fn claim_seat<'info>(
+    seat_manager_program: &AccountInfo<'info>,
+    phoenix_program: &AccountInfo<'info>,
+    log_authority: &AccountInfo<'info>,
+    market: &AccountInfo<'info>,
+    seat_manager: &AccountInfo<'info>,
+    seat_deposit_collector: &AccountInfo<'info>,
+    trader: &AccountInfo<'info>,
+    seat: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_signed(
+        &phoenix_seat_manager::instruction_builders::create_claim_seat_instruction(
+            trader.key,
+            market.key,
+        ),
+        &[
+            seat_manager_program.clone(),
+            phoenix_program.clone(),
+            log_authority.clone(),
+            market.clone(),
+            seat_manager.clone(),
+            seat_deposit_collector.clone(),
+            trader.clone(),
+            seat.clone(),
+            system_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
 fn get_best_bid_and_ask(
     market: &dyn Market<Pubkey, FIFOOrderId, FIFORestingOrder, OrderPacket>,
     trader_index: u64,
@@ -113,6 +451,77 @@ fn get_ask_price_in_ticks(
     fair_price_in_ticks + edge_in_ticks
 }
 
+fn get_limit_order_packet(
+    side: Side,
+    price_in_ticks: u64,
+    size_in_base_lots: u64,
+    client_order_id: u128,
+    phoenix_strategy: &PhoenixStrategyState,
+) -> Result<OrderPacket> {
+    Ok(OrderPacket::Limit {
+        side,
+        price_in_ticks: Ticks::new(price_in_ticks),
+        num_base_lots: BaseLots::new(size_in_base_lots),
+        self_trade_behavior: SelfTradeBehavior::try_from(phoenix_strategy.self_trade_behavior)?
+            .to_phoenix(),
+        match_limit: None,
+        client_order_id,
+        use_only_deposited_funds: false,
+        last_valid_slot: None,
+        last_valid_unix_timestamp_in_seconds: None,
+        fail_silently_on_insufficient_funds: phoenix_strategy.fail_silently_on_insufficient_funds,
+    })
+}
+
+fn get_order_notional_in_quote_atoms(
+    price_in_ticks: u64,
+    size_in_base_lots: u64,
+    header: &MarketHeader,
+    base_lots_per_base_unit: u64,
+) -> u64 {
+    (price_in_ticks as u128
+        * size_in_base_lots as u128
+        * header.get_tick_size_in_quote_atoms_per_base_unit().as_u128()
+        / base_lots_per_base_unit as u128)
+        .min(u64::MAX as u128) as u64
+}
+
+/// Returns how far, in basis points, the furthest side of the book is from the fair price.
+/// Empty sides are ignored.
+fn get_book_deviation_in_bps(fair_price_in_ticks: u64, best_bid: u64, best_ask: u64) -> u64 {
+    if fair_price_in_ticks == 0 {
+        return u64::MAX;
+    }
+    let deviation_in_bps = |price_in_ticks: u64| {
+        (price_in_ticks.abs_diff(fair_price_in_ticks) as u128 * 10_000
+            / fair_price_in_ticks as u128)
+            .min(u64::MAX as u128) as u64
+    };
+    let mut deviation = 0;
+    if best_bid > 1 {
+        deviation = deviation.max(deviation_in_bps(best_bid));
+    }
+    if best_ask < u64::MAX {
+        deviation = deviation.max(deviation_in_bps(best_ask));
+    }
+    deviation
+}
+
+/// Checks the quoting parameters every strategy needs, an edge of 100% or more would push the
+/// bid price below zero
+fn validate_quote_params(quote_edge_in_bps: u64, quote_size_in_quote_atoms: u64) -> Result<()> {
+    require!(quote_edge_in_bps > 0, StrategyError::EdgeMustBeNonZero);
+    require!(
+        quote_edge_in_bps < 10_000,
+        StrategyError::InvalidStrategyParams
+    );
+    require!(
+        quote_size_in_quote_atoms > 0,
+        StrategyError::InvalidStrategyParams
+    );
+    Ok(())
+}
+
 fn get_fair_price_in_ticks(
     base_price: u128,
     quote_price: u128,
@@ -132,23 +541,31 @@ pub enum PriceImprovementBehavior {
     Ignore,
 }
 
-impl PriceImprovementBehavior {
-    pub fn to_u8(&self) -> u8 {
-        match self {
-            PriceImprovementBehavior::Ubermensch => 0,
-            PriceImprovementBehavior::Join => 1,
-            PriceImprovementBehavior::Dime => 2,
-            PriceImprovementBehavior::Ignore => 3,
-        }
-    }
+u8_enum!(
+    PriceImprovementBehavior,
+    StrategyError::InvalidPriceImprovementBehavior,
+    { Ubermensch = 0, Join = 1, Dime = 2, Ignore = 3 }
+);
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum SelfTradeBehavior {
+    Abort,
+    CancelProvide,
+    DecrementTake,
+}
 
-    pub fn from_u8(byte: u8) -> Self {
-        match byte {
-            0 => PriceImprovementBehavior::Ubermensch,
-            1 => PriceImprovementBehavior::Join,
-            2 => PriceImprovementBehavior::Dime,
-            3 => PriceImprovementBehavior::Ignore,
-            _ => panic!("Invalid PriceImprovementBehavior"),
+u8_enum!(
+    SelfTradeBehavior,
+    StrategyError::InvalidSelfTradeBehavior,
+    { Abort = 0, CancelProvide = 1, DecrementTake = 2 }
+);
+
+impl SelfTradeBehavior {
+    pub fn to_phoenix(&self) -> phoenix::state::SelfTradeBehavior {
+        match self {
+            SelfTradeBehavior::Abort => phoenix::state::SelfTradeBehavior::Abort,
+            SelfTradeBehavior::CancelProvide => phoenix::state::SelfTradeBehavior::CancelProvide,
+            SelfTradeBehavior::DecrementTake => phoenix::state::SelfTradeBehavior::DecrementTake,
         }
     }
 }
@@ -171,50 +588,113 @@ pub struct PhoenixStrategyState {
     pub quote_edge_in_bps: u64,
     /// Order notional size in quote atoms
     pub quote_size_in_quote_atoms: u64,
-    /// If set to true, the orders will never cross the spread
+    /// If set to true, the orders will never cross the spread, otherwise they are sent as limit
+    /// orders that can take and use the self-trade behavior
     pub post_only: bool,
     /// Determines whether/how to improve BBO
     pub price_improvement_behavior: u8,
-    padding: [u8; 6],
-}
-
-#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
-pub struct OrderParams {
-    pub fair_price_in_quote_atoms_per_raw_base_unit: u64,
-    pub strategy_params: StrategyParams,
-    pub use_oracle: bool,
-}
-
-#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
-pub struct StrategyParams {
-    pub quote_edge_in_bps: Option<u64>,
-    pub quote_size_in_quote_atoms: Option<u64>,
-    pub price_improvement_behavior: Option<PriceImprovementBehavior>,
-    pub post_only: Option<bool>,
+    /// If set to true, update_quotes refuses to place orders
+    pub paused: bool,
+    /// Layout version of the account, 0 for accounts created before versioning
+    pub version: u8,
+    /// Set once a vault pools LP capital into the strategy, the fair price can't be overridden
+    pub has_vault: bool,
+    padding: [u8; 3],
+    /// Optional key that is allowed to pause the strategy, Pubkey::default() if unset
+    pub guardian: Pubkey,
+    // Risk parameters
+    /// Maximum distance between the best bid/ask of other traders and the oracle fair price
+    /// before quotes are pulled, 0 disables the check
+    pub max_book_deviation_in_bps: u64,
+    /// Minimum number of slots between two update_quotes calls, 0 disables the check
+    pub min_slots_between_updates: u64,
+    /// Maximum notional of a single order in quote atoms, 0 disables the check
+    pub max_order_notional_in_quote_atoms: u64,
+    /// Maximum number of cancel-replace cycles placing orders per window, cancels alone are
+    /// never limited, 0 disables the check
+    pub max_cancel_replace_cycles: u64,
+    /// Length of the cancel-replace window in slots
+    pub cancel_replace_window_in_slots: u64,
+    pub cancel_replace_window_start_slot: u64,
+    pub cancel_replace_cycles_in_window: u64,
+    /// Lowest price in ticks we are allowed to quote at, 0 disables the bound
+    pub min_quote_price_in_ticks: u64,
+    /// Highest price in ticks we are allowed to quote at, 0 disables the bound
+    pub max_quote_price_in_ticks: u64,
+    /// Number of slots without an update after which anyone can cancel our quotes, 0 disables
+    pub heartbeat_in_slots: u64,
+    // Execution parameters
+    /// What happens when a limit order would match against one of our own orders
+    pub self_trade_behavior: u8,
+    /// If set to true, a side without enough funds is skipped instead of failing the transaction
+    pub fail_silently_on_insufficient_funds: bool,
+    execution_padding: [u8; 6],
+    /// Quote atoms that are never committed to orders
+    pub quote_reserve_in_quote_atoms: u64,
+    /// Base atoms that are never committed to orders
+    pub base_reserve_in_base_atoms: u64,
+    /// Sides that can't be funded for at least this size are skipped
+    pub min_order_size_in_base_lots: u64,
+    /// Minimum distance in ticks between the fair price and the BBO for Ubermensch mode to keep
+    /// quoting at the BBO
+    pub margin_in_ticks: u64,
+    /// Optional key that can update and cancel quotes but not change parameters, move funds or
+    /// override the oracle fair price, Pubkey::default() if unset
+    pub operator: Pubkey,
+    /// Wallet that created the strategy, differs from the trader when the program holds the seat
+    pub owner: Pubkey,
+    /// Phoenix seat of the trader on the market
+    pub seat: Pubkey,
+    // Fill statistics. Limit orders book what they took when placed from the change of the
+    // trader's holdings. Resting orders book what they lost on the book up to the proceeds
+    // credited to the trader's free lots, so a fill of another order of the trader on the same
+    // side can still be booked as a fill of ours
+    pub num_bid_fills: u64,
+    pub num_ask_fills: u64,
+    pub base_lots_bought: u64,
+    pub base_lots_sold: u64,
+    pub quote_atoms_paid: u64,
+    pub quote_atoms_received: u64,
+    /// Volume weighted average price of our filled bids
+    pub average_buy_price_in_ticks: u64,
+    /// Volume weighted average price of our filled asks
+    pub average_sell_price_in_ticks: u64,
+    /// Total number of updates that cancelled or placed orders
+    pub num_cancel_replace_cycles: u64,
+    /// Free lots of the trader right after the tracked orders were placed, moved by seat
+    /// deposits and withdrawals and by the fills booked since
+    pub quote_lots_free_baseline: i64,
+    pub base_lots_free_baseline: i64,
+    // PnL, tracked with the average cost of the position built by our fills
+    /// Positive when long, negative when short
+    pub net_position_in_base_lots: i64,
+    /// Average price the open position was entered at after fees, 0 when flat
+    pub average_entry_price_in_ticks: u64,
+    pub realized_pnl_in_quote_atoms: i64,
+    /// Open position marked against the oracle fair price of the last update
+    pub unrealized_pnl_in_quote_atoms: i64,
+    pub last_mark_price_in_ticks: u64,
+    /// Quote atoms paid for the open position including fees, negative for the quote atoms
+    /// received when short
+    pub position_cost_in_quote_atoms: i64,
 }
 
-#[program]
-pub mod uber_mm {
-    use super::*;
-
-    pub fn initialize(
-        ctx: Context<Initialize>, 
-        quote_edge_in_bps: u64, 
+impl PhoenixStrategyState {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        owner: Pubkey,
+        trader: Pubkey,
+        market: Pubkey,
+        quote_edge_in_bps: u64,
         quote_size_in_quote_atoms: u64,
-        price_improvement_behavior: u8,
+        price_improvement_behavior: PriceImprovementBehavior,
         post_only: bool,
-    ) -> Result<()> {
-        require!(
-            quote_edge_in_bps > 0,
-            StrategyError::EdgeMustBeNonZero
-        );
-        load_header(&ctx.accounts.market)?;
-        let clock = Clock::get()?;
-        msg!("Initializing Phoenix Strategy");
-        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_init()?;
-        *phoenix_strategy = PhoenixStrategyState {
-            trader: *ctx.accounts.user.key,
-            market: *ctx.accounts.market.key,
+        guardian: Option<Pubkey>,
+        clock: &Clock,
+    ) -> Self {
+        PhoenixStrategyState {
+            trader,
+            market,
             bid_order_sequence_number: 0,
             bid_price_in_ticks: 0,
             initial_bid_size_in_base_lots: 0,
@@ -223,299 +703,904 @@ pub mod uber_mm {
             initial_ask_size_in_base_lots: 0,
             last_update_slot: clock.slot,
             last_update_unix_timestamp: clock.unix_timestamp,
-            quote_edge_in_bps: quote_edge_in_bps,
-            quote_size_in_quote_atoms: quote_size_in_quote_atoms,
-            post_only: post_only,
-            price_improvement_behavior: price_improvement_behavior,
-            padding: [0; 6],
-        };
-        Ok(())
+            quote_edge_in_bps,
+            quote_size_in_quote_atoms,
+            post_only,
+            price_improvement_behavior: price_improvement_behavior.to_u8(),
+            paused: false,
+            version: PHOENIX_STRATEGY_VERSION,
+            has_vault: false,
+            padding: [0; 3],
+            guardian: guardian.unwrap_or_default(),
+            max_book_deviation_in_bps: 0,
+            min_slots_between_updates: 0,
+            max_order_notional_in_quote_atoms: 0,
+            max_cancel_replace_cycles: 0,
+            cancel_replace_window_in_slots: 0,
+            cancel_replace_window_start_slot: clock.slot,
+            cancel_replace_cycles_in_window: 0,
+            min_quote_price_in_ticks: 0,
+            max_quote_price_in_ticks: 0,
+            heartbeat_in_slots: 0,
+            self_trade_behavior: SelfTradeBehavior::CancelProvide.to_u8(),
+            fail_silently_on_insufficient_funds: false,
+            execution_padding: [0; 6],
+            quote_reserve_in_quote_atoms: 0,
+            base_reserve_in_base_atoms: 0,
+            min_order_size_in_base_lots: 1,
+            margin_in_ticks: 0,
+            operator: Pubkey::default(),
+            owner,
+            seat: phoenix::program::get_seat_address(&market, &trader).0,
+            num_bid_fills: 0,
+            num_ask_fills: 0,
+            base_lots_bought: 0,
+            base_lots_sold: 0,
+            quote_atoms_paid: 0,
+            quote_atoms_received: 0,
+            average_buy_price_in_ticks: 0,
+            average_sell_price_in_ticks: 0,
+            num_cancel_replace_cycles: 0,
+            quote_lots_free_baseline: 0,
+            base_lots_free_baseline: 0,
+            net_position_in_base_lots: 0,
+            average_entry_price_in_ticks: 0,
+            realized_pnl_in_quote_atoms: 0,
+            unrealized_pnl_in_quote_atoms: 0,
+            last_mark_price_in_ticks: 0,
+            position_cost_in_quote_atoms: 0,
+        }
     }
 
-    pub fn update_quotes(
-        ctx: Context<UpdateQuotes>,
-        fair_price_in_quote_atoms_per_raw_base_unit: u64,
-        quote_edge_in_bps: u64, 
-        quote_size_in_quote_atoms: u64,
-        price_improvement_behavior: u8,
-        post_only: bool,
-        use_oracle: bool,
-        margin: u64,
+    /// Upgrades the account one version at a time, new fields are zeroed by the realloc.
+    /// The layout only grows at the end and every change bumps PHOENIX_STRATEGY_VERSION
+    pub fn migrate(
+        &mut self,
+        slot: u64,
+        trader_state: &TraderState,
+        header: &MarketHeader,
+        base_lots_per_base_unit: u64,
     ) -> Result<()> {
-        let UpdateQuotes {
-            phoenix_strategy,
-            user,
-            phoenix_program,
-            log_authority,
-            market: market_account,
-            seat,
-            quote_account,
-            base_account,
-            quote_vault,
-            base_vault,
-            token_program,
-        } = ctx.accounts;
+        while self.version < PHOENIX_STRATEGY_VERSION {
+            match self.version {
+                0 => {
+                    self.cancel_replace_window_start_slot = slot;
+                    self.self_trade_behavior = SelfTradeBehavior::CancelProvide.to_u8();
+                    self.min_order_size_in_base_lots = 1;
+                }
+                1 => {
+                    self.owner = self.trader;
+                }
+                2 => {
+                    self.seat = phoenix::program::get_seat_address(&self.market, &self.trader).0;
+                }
+                3 => {
+                    // Fills of orders placed before the migration are booked from here on
+                    self.quote_lots_free_baseline = trader_state.quote_lots_free.as_u64() as i64;
+                    self.base_lots_free_baseline = trader_state.base_lots_free.as_u64() as i64;
+                }
+                4 => {
+                    self.position_cost_in_quote_atoms = get_ticks_times_lots_in_quote_atoms(
+                        self.average_entry_price_in_ticks as i128
+                            * self.net_position_in_base_lots as i128,
+                        header,
+                        base_lots_per_base_unit,
+                    );
+                }
+                version => {
+                    msg!("No migration from strategy version {}", version);
+                    return err!(StrategyError::UnsupportedStrategyVersion);
+                }
+            }
+            self.version += 1;
+        }
+        Ok(())
+    }
 
-        let mut phoenix_strategy = phoenix_strategy.load_mut()?;
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        *key == self.owner || (self.guardian != Pubkey::default() && *key == self.guardian)
+    }
 
-        // Update timestamps
-        let clock = Clock::get()?;
-        phoenix_strategy.last_update_slot = clock.slot;
-        phoenix_strategy.last_update_unix_timestamp = clock.unix_timestamp;
+    pub fn can_operate(&self, key: &Pubkey) -> bool {
+        *key == self.owner || (self.operator != Pubkey::default() && *key == self.operator)
+    }
+
+    pub fn is_stale(&self, slot: u64) -> bool {
+        self.heartbeat_in_slots > 0
+            && slot > self.last_update_slot.saturating_add(self.heartbeat_in_slots)
+    }
+
+    /// True if the current cancel-replace window has no cycles left
+    pub fn is_cancel_replace_budget_exhausted(&self, slot: u64) -> bool {
+        self.max_cancel_replace_cycles > 0
+            && slot
+                < self
+                    .cancel_replace_window_start_slot
+                    .saturating_add(self.cancel_replace_window_in_slots)
+            && self.cancel_replace_cycles_in_window >= self.max_cancel_replace_cycles
+    }
 
-        // Update the strategy parameters
-        if let edge = quote_edge_in_bps {
-            if edge > 0 {
-                phoenix_strategy.quote_edge_in_bps = edge;
+    pub fn is_price_in_band(&self, price_in_ticks: u64) -> bool {
+        price_in_ticks >= self.min_quote_price_in_ticks
+            && (self.max_quote_price_in_ticks == 0 || price_in_ticks <= self.max_quote_price_in_ticks)
+    }
+
+    /// Moves the free lots baselines by funds moved in or out of the seat, so they aren't
+    /// mistaken for fill proceeds
+    pub fn shift_free_lots_baseline(&mut self, before: &TraderState, after: &TraderState) {
+        self.quote_lots_free_baseline +=
+            after.quote_lots_free.as_u64() as i64 - before.quote_lots_free.as_u64() as i64;
+        self.base_lots_free_baseline +=
+            after.base_lots_free.as_u64() as i64 - before.base_lots_free.as_u64() as i64;
+    }
+
+    pub fn clear_orders(&mut self) {
+        self.bid_order_sequence_number = 0;
+        self.bid_price_in_ticks = 0;
+        self.initial_bid_size_in_base_lots = 0;
+        self.ask_order_sequence_number = 0;
+        self.ask_price_in_ticks = 0;
+        self.initial_ask_size_in_base_lots = 0;
+    }
+
+    pub fn clear_order(&mut self, side: Side) {
+        match side {
+            Side::Bid => {
+                self.bid_order_sequence_number = 0;
+                self.bid_price_in_ticks = 0;
+                self.initial_bid_size_in_base_lots = 0;
+            }
+            Side::Ask => {
+                self.ask_order_sequence_number = 0;
+                self.ask_price_in_ticks = 0;
+                self.initial_ask_size_in_base_lots = 0;
             }
         }
-        if let size = quote_size_in_quote_atoms {
-            phoenix_strategy.quote_size_in_quote_atoms = size;
-        }
-        if let post_only = post_only {
-            phoenix_strategy.post_only = post_only;
-        }
-        if let price_improvement_behavior = price_improvement_behavior
-        {
-            phoenix_strategy.price_improvement_behavior = price_improvement_behavior;
+    }
+
+    pub fn record_fill(
+        &mut self,
+        side: Side,
+        price_in_ticks: u64,
+        base_lots: u64,
+        quote_atoms: u64,
+    ) {
+        let (num_fills, base_lots_filled, quote_atoms_filled, average_price_in_ticks) = match side {
+            Side::Bid => (
+                &mut self.num_bid_fills,
+                &mut self.base_lots_bought,
+                &mut self.quote_atoms_paid,
+                &mut self.average_buy_price_in_ticks,
+            ),
+            Side::Ask => (
+                &mut self.num_ask_fills,
+                &mut self.base_lots_sold,
+                &mut self.quote_atoms_received,
+                &mut self.average_sell_price_in_ticks,
+            ),
+        };
+        *average_price_in_ticks = ((*average_price_in_ticks as u128 * *base_lots_filled as u128
+            + price_in_ticks as u128 * base_lots as u128)
+            / (*base_lots_filled as u128 + base_lots as u128)) as u64;
+        *num_fills += 1;
+        *base_lots_filled = base_lots_filled.saturating_add(base_lots);
+        *quote_atoms_filled = quote_atoms_filled.saturating_add(quote_atoms);
+    }
+
+    /// Moves the average cost position by a fill of base_lots for quote_atoms, fees included.
+    /// The part of a fill that reduces the position realizes PnL against the cost of the
+    /// lots it closes
+    pub fn update_cost_basis(
+        &mut self,
+        side: Side,
+        base_lots: u64,
+        quote_atoms: u64,
+        header: &MarketHeader,
+        base_lots_per_base_unit: u64,
+    ) {
+        let position = self.net_position_in_base_lots as i128;
+        // Quote atoms flow out for bids and in for asks
+        let (fill, quote_flow) = match side {
+            Side::Bid => (base_lots as i128, -(quote_atoms as i128)),
+            Side::Ask => (-(base_lots as i128), quote_atoms as i128),
+        };
+        let mut cost = self.position_cost_in_quote_atoms as i128;
+        let mut opening_quote_flow = quote_flow;
+        if position != 0 && position.signum() != fill.signum() {
+            let closed_base_lots = position.abs().min(fill.abs());
+            let closed_cost = cost * closed_base_lots / position.abs();
+            let closing_quote_flow = quote_flow * closed_base_lots / fill.abs();
+            self.realized_pnl_in_quote_atoms =
+                (self.realized_pnl_in_quote_atoms as i128 + closing_quote_flow - closed_cost)
+                    .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+            cost -= closed_cost;
+            opening_quote_flow -= closing_quote_flow;
         }
+        // The rest of the fill opens or grows the position
+        cost -= opening_quote_flow;
+        let position = position + fill;
+        self.net_position_in_base_lots = position as i64;
+        self.position_cost_in_quote_atoms = cost.clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+        let tick_size_in_quote_atoms = header
+            .get_tick_size_in_quote_atoms_per_base_unit()
+            .as_u128();
+        self.average_entry_price_in_ticks = if position == 0 {
+            0
+        } else {
+            (cost.unsigned_abs() * base_lots_per_base_unit as u128
+                / (position.unsigned_abs() * tick_size_in_quote_atoms)) as u64
+        };
+    }
 
-        // Load market
-        let header = load_header(market_account)?;
-        let market_data = market_account.data.borrow();
-        let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
-        let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
-            .map_err(|_| {
-                msg!("Failed to deserialize market");
-                StrategyError::FailedToDeserializePhoenixMarket
-            })?
-            .inner;
-        let mut fair_price_in_ticks = fair_price_in_quote_atoms_per_raw_base_unit;
+    /// Marks the open position against the fair price
+    pub fn mark_to_price(
+        &mut self,
+        fair_price_in_ticks: u64,
+        header: &MarketHeader,
+        base_lots_per_base_unit: u64,
+    ) {
+        self.last_mark_price_in_ticks = fair_price_in_ticks;
+        let value_in_quote_atoms = get_ticks_times_lots_in_quote_atoms(
+            fair_price_in_ticks as i128 * self.net_position_in_base_lots as i128,
+            header,
+            base_lots_per_base_unit,
+        );
+        self.unrealized_pnl_in_quote_atoms =
+            value_in_quote_atoms.saturating_sub(self.position_cost_in_quote_atoms);
+    }
+}
+
+/// Accounts created before the layout grew are too small to load until migrate_strategy
+/// reallocs them, loading them would panic instead of failing
+pub fn is_strategy_layout_current(phoenix_strategy: &AccountInfo) -> bool {
+    phoenix_strategy.data_len() >= 8 + std::mem::size_of::<PhoenixStrategyState>()
+}
+
+/// Converts a signed amount of ticks times base lots into quote atoms
+fn get_ticks_times_lots_in_quote_atoms(
+    ticks_times_base_lots: i128,
+    header: &MarketHeader,
+    base_lots_per_base_unit: u64,
+) -> i64 {
+    (ticks_times_base_lots * header.get_tick_size_in_quote_atoms_per_base_unit().as_u128() as i128
+        / base_lots_per_base_unit as i128)
+        .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// PnL of a strategy as returned by get_pnl
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct StrategyPnl {
+    pub realized_pnl_in_quote_atoms: i64,
+    pub unrealized_pnl_in_quote_atoms: i64,
+    pub net_position_in_base_lots: i64,
+    pub average_entry_price_in_ticks: u64,
+    pub last_mark_price_in_ticks: u64,
+}
+
+#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct StrategyParams {
+    pub quote_edge_in_bps: Option<u64>,
+    pub quote_size_in_quote_atoms: Option<u64>,
+    pub price_improvement_behavior: Option<PriceImprovementBehavior>,
+    pub post_only: Option<bool>,
+    pub margin_in_ticks: Option<u64>,
+}
 
-        // checking if oracle is used to calculate the fair price
+#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct RiskParams {
+    pub max_book_deviation_in_bps: Option<u64>,
+    pub min_slots_between_updates: Option<u64>,
+    pub max_order_notional_in_quote_atoms: Option<u64>,
+    pub max_cancel_replace_cycles: Option<u64>,
+    pub cancel_replace_window_in_slots: Option<u64>,
+    pub min_quote_price_in_ticks: Option<u64>,
+    pub max_quote_price_in_ticks: Option<u64>,
+    pub heartbeat_in_slots: Option<u64>,
+}
 
-        if use_oracle {
-            msg!("Using oracle to calculate the fair price");
-            let mut pyth_account: &AccountInfo = &ctx.remaining_accounts[0];
-            let mut oracle_price = Price::load(&pyth_account)?;
-            msg!("oracle price = {}, oracle expo = {}", oracle_price.price, oracle_price.expo);
-            // calculating the price by multiplying oracle price on 10^6 and dividing it on 10^expo
-            let base_fair_price = (BIG_NUMBER as u128 * oracle_price.price as u128 / (u64::pow(10, (-oracle_price.expo) as u32) as u128)) as u128;
+#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct ExecutionParams {
+    pub self_trade_behavior: Option<SelfTradeBehavior>,
+    pub fail_silently_on_insufficient_funds: Option<bool>,
+    pub quote_reserve_in_quote_atoms: Option<u64>,
+    pub base_reserve_in_base_atoms: Option<u64>,
+    pub min_order_size_in_base_lots: Option<u64>,
+}
 
-            pyth_account = &ctx.remaining_accounts[1];
-            oracle_price = Price::load(&pyth_account)?;
+/// Checks whether the strategy can quote, returns the oracle prices unless they are overridden
+pub fn check_strategy_quotes<'info>(
+    accounts: &UpdateQuotes<'info>,
+    oracle_accounts: &[AccountInfo<'info>],
+    fair_price_in_quote_atoms_per_raw_base_unit: Option<u64>,
+) -> Result<Option<(u128, u128, u128)>> {
+    let phoenix_strategy = accounts.phoenix_strategy.load()?;
+    require!(
+        phoenix_strategy.can_operate(accounts.authority.key),
+        StrategyError::InvalidStrategyAuthority
+    );
+    // A fair price picked by the operator could trade the inventory away at any price, so
+    // only the owner can skip the oracle, and not at all when LP capital is at stake
+    require!(
+        fair_price_in_quote_atoms_per_raw_base_unit.is_none()
+            || (*accounts.authority.key == phoenix_strategy.owner && !phoenix_strategy.has_vault),
+        StrategyError::FairPriceOverrideNotAllowed
+    );
+    require!(
+        phoenix_strategy.version == PHOENIX_STRATEGY_VERSION,
+        StrategyError::StrategyNeedsMigration
+    );
+    require!(!phoenix_strategy.paused, StrategyError::StrategyPaused);
+    require!(
+        Clock::get()?.slot
+            >= phoenix_strategy
+                .last_update_slot
+                .saturating_add(phoenix_strategy.min_slots_between_updates),
+        StrategyError::UpdateTooFrequent
+    );
+    if fair_price_in_quote_atoms_per_raw_base_unit.is_some() {
+        return Ok(None);
+    }
+    require!(
+        oracle_accounts.len() >= 2,
+        StrategyError::InvalidOracleAccount
+    );
+    let prices = accounts
+        .market_config
+        .load()?
+        .load_prices_with_confidence(
+            &accounts.phoenix_strategy.key(),
+            &oracle_accounts[0],
+            &oracle_accounts[1],
+        )?;
+    Ok(Some(prices))
+}
 
-            let quote_fair_price = (BIG_NUMBER as u128 * oracle_price.price as u128 / (u64::pow(10, (-oracle_price.expo) as u32) as u128)) as u128;
-            msg!("Base price = {}, quote price = {}", base_fair_price, quote_fair_price);
+/// Runs the quoting logic of a strategy that passed check_strategy_quotes, shared by
+/// update_quotes and update_portfolio. The oracles can be followed by the strategy history
+/// account, which gets a record of every update
+pub fn update_strategy_quotes<'info>(
+    accounts: &mut UpdateQuotes<'info>,
+    oracle_accounts: &[AccountInfo<'info>],
+    fair_price_in_quote_atoms_per_raw_base_unit: Option<u64>,
+    oracle_prices: Option<(u128, u128, u128)>,
+) -> Result<()> {
+    let mut record = UpdateRecord {
+        slot: Clock::get()?.slot,
+        ..Default::default()
+    };
+    quote_strategy(
+        accounts,
+        fair_price_in_quote_atoms_per_raw_base_unit,
+        oracle_prices,
+        &mut record,
+    )?;
 
-            fair_price_in_ticks = get_fair_price_in_ticks(
-                base_fair_price,
-                quote_fair_price,
-                &header,
-            );
+    if let Some(history_account) = oracle_accounts.get(2) {
+        let history_loader = AccountLoader::<StrategyHistory>::try_from(history_account)?;
+        let mut history = history_loader.load_mut()?;
+        require_keys_eq!(
+            history.phoenix_strategy,
+            accounts.phoenix_strategy.key(),
+            StrategyError::InvalidHistoryAccount
+        );
+        {
+            let phoenix_strategy = accounts.phoenix_strategy.load()?;
+            record.bid_price_in_ticks = phoenix_strategy.bid_price_in_ticks;
+            record.bid_size_in_base_lots = phoenix_strategy.initial_bid_size_in_base_lots;
+            record.ask_price_in_ticks = phoenix_strategy.ask_price_in_ticks;
+            record.ask_size_in_base_lots = phoenix_strategy.initial_ask_size_in_base_lots;
         }
+        let header = load_header(&accounts.market)?;
+        (record.quote_atoms_held, record.base_atoms_held) = get_trader_holdings(
+            &accounts.market,
+            &header,
+            accounts.trader.key,
+            &accounts.quote_account,
+            &accounts.base_account,
+        )?;
+        history.push(record);
+    }
+    Ok(())
+}
 
+fn quote_strategy<'info>(
+    accounts: &mut UpdateQuotes<'info>,
+    fair_price_in_quote_atoms_per_raw_base_unit: Option<u64>,
+    oracle_prices: Option<(u128, u128, u128)>,
+    record: &mut UpdateRecord,
+) -> Result<()> {
+    let UpdateQuotes {
+        phoenix_strategy: phoenix_strategy_account,
+        authority: _,
+        trader,
+        phoenix_program,
+        log_authority,
+        market: market_account,
+        market_config: _,
+        seat,
+        quote_account,
+        base_account,
+        quote_vault,
+        base_vault,
+        token_program,
+    } = accounts;
 
-        msg!("{} {}", header.raw_base_units_per_base_unit as u64, header.get_tick_size_in_quote_atoms_per_base_unit().as_u64());
+    let mut phoenix_strategy = phoenix_strategy_account.load_mut()?;
+    let strategy_key = phoenix_strategy_account.key();
+    let trader_authority_bump = get_trader_authority_bump(&strategy_key, trader)?;
+    let bump_seed = [trader_authority_bump.unwrap_or_default()];
+    let trader_seeds: &[&[u8]] = &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+    let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+        &[trader_seeds]
+    } else {
+        &[]
+    };
 
-        // Compute quote prices
-        let mut bid_price_in_ticks = get_bid_price_in_ticks(
-            fair_price_in_ticks,
+    // Update timestamps
+    let clock = Clock::get()?;
+    phoenix_strategy.last_update_slot = clock.slot;
+    phoenix_strategy.last_update_unix_timestamp = clock.unix_timestamp;
+
+    // Load market
+    let header = load_header(market_account)?;
+    let market_data = market_account.data.borrow();
+    let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
+    let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
+        .map_err(|_| {
+            msg!("Failed to deserialize market");
+            StrategyError::FailedToDeserializePhoenixMarket
+        })?
+        .inner;
+    record_fills(&strategy_key, &mut phoenix_strategy, market, &header);
+    // The oracle is used to calculate the fair price unless it is overridden
+    let use_oracle = fair_price_in_quote_atoms_per_raw_base_unit.is_none();
+    let mut fair_price_in_ticks = fair_price_in_quote_atoms_per_raw_base_unit.unwrap_or_default();
+
+    if let Some((base_fair_price, quote_fair_price, base_confidence)) = oracle_prices {
+        record.oracle_confidence_in_ticks =
+            get_fair_price_in_ticks(base_confidence, quote_fair_price, &header);
+
+        fair_price_in_ticks = get_fair_price_in_ticks(
+            base_fair_price,
+            quote_fair_price,
             &header,
-            phoenix_strategy.quote_edge_in_bps,
         );
-
-        let mut ask_price_in_ticks = get_ask_price_in_ticks(
+        phoenix_strategy.mark_to_price(
             fair_price_in_ticks,
             &header,
-            phoenix_strategy.quote_edge_in_bps,
+            market.get_base_lots_per_base_unit().as_u64(),
         );
+    }
+    record.fair_price_in_ticks = fair_price_in_ticks;
 
-        // Returns the best bid and ask prices that are not placed by the trader
-        let trader_index = market.get_trader_index(&user.key()).unwrap_or(u32::MAX) as u64;
-        let (best_bid, best_ask) = get_best_bid_and_ask(market, trader_index);
-
-        msg!("Current market: {} @ {}, our: {} {}", best_bid, best_ask, bid_price_in_ticks, ask_price_in_ticks);
-        msg!("fair price: {}", fair_price_in_ticks);
-        let price_improvement_behavior =
-            PriceImprovementBehavior::from_u8(phoenix_strategy.price_improvement_behavior);
-        match price_improvement_behavior {
-            PriceImprovementBehavior::Ubermensch => {
-                // we check current ask/bid price's relationship to fair price
-                // if difference between ask/bid price and fair price is more than margin
-                // then we still trade
-                ask_price_in_ticks = ask_price_in_ticks.max(best_ask);
-                bid_price_in_ticks = bid_price_in_ticks.min(best_bid);
-                if best_ask > fair_price_in_ticks + margin {
-                    ask_price_in_ticks = best_ask;
-                }
+    // Compute quote prices
+    let mut bid_price_in_ticks = get_bid_price_in_ticks(
+        fair_price_in_ticks,
+        &header,
+        phoenix_strategy.quote_edge_in_bps,
+    );
 
-                if best_bid < fair_price_in_ticks - margin {
-                    bid_price_in_ticks = best_bid;
-                }
+    let mut ask_price_in_ticks = get_ask_price_in_ticks(
+        fair_price_in_ticks,
+        &header,
+        phoenix_strategy.quote_edge_in_bps,
+    );
 
-                msg!("ask price = {}, bid price = {}", ask_price_in_ticks, bid_price_in_ticks);
+    // Returns the best bid and ask prices that are not placed by the trader
+    let trader_index = market.get_trader_index(&trader.key()).unwrap_or(u32::MAX) as u64;
+    let (best_bid, best_ask) = get_best_bid_and_ask(market, trader_index);
+    record.best_bid_in_ticks = best_bid;
+    record.best_ask_in_ticks = best_ask;
 
+    // If the rest of the book disagrees with the oracle by too much, either the oracle is stale
+    // or the market is moving, in both cases we pull our quotes instead of pricing into it
+    if use_oracle && phoenix_strategy.max_book_deviation_in_bps > 0 {
+        let deviation_in_bps =
+            get_book_deviation_in_bps(fair_price_in_ticks, best_bid, best_ask);
+        if deviation_in_bps > phoenix_strategy.max_book_deviation_in_bps {
+            emit!(CircuitBreakerTripped {
+                strategy: strategy_key,
+                best_bid_in_ticks: best_bid,
+                best_ask_in_ticks: best_ask,
+                fair_price_in_ticks,
+                deviation_in_bps,
+                max_book_deviation_in_bps: phoenix_strategy.max_book_deviation_in_bps,
+            });
+            drop(market_data);
+            cancel_all_orders(
+                phoenix_program,
+                log_authority,
+                trader,
+                market_account,
+                signer_seeds,
+            )?;
+            phoenix_strategy.clear_orders();
+            emit!(OrdersCancelled {
+                strategy: strategy_key,
+                order_sequence_numbers: vec![],
+                cancelled_all: true,
+            });
+            return Ok(());
+        }
+    }
+    let price_improvement_behavior =
+        PriceImprovementBehavior::try_from(phoenix_strategy.price_improvement_behavior)?;
+    match price_improvement_behavior {
+        PriceImprovementBehavior::Ubermensch => {
+            // we check current ask/bid price's relationship to fair price
+            // if difference between ask/bid price and fair price is more than margin
+            // then we still trade
+            ask_price_in_ticks = ask_price_in_ticks.max(best_ask);
+            bid_price_in_ticks = bid_price_in_ticks.min(best_bid);
+            if best_ask > fair_price_in_ticks.saturating_add(phoenix_strategy.margin_in_ticks) {
+                ask_price_in_ticks = best_ask;
             }
-            PriceImprovementBehavior::Join => {
-                // If price_improvement_behavior is set to Join, we will always join the best bid and ask
-                // if our quote prices are within the spread
-                ask_price_in_ticks = ask_price_in_ticks.max(best_ask);
-                bid_price_in_ticks = bid_price_in_ticks.min(best_bid);
-            }
-            PriceImprovementBehavior::Dime => {
-                // If price_improvement_behavior is set to Dime, we will never price improve by more than 1 tick
-                ask_price_in_ticks = ask_price_in_ticks.max(best_ask - 1);
-                bid_price_in_ticks = bid_price_in_ticks.min(best_bid + 1);
-            }
-            PriceImprovementBehavior::Ignore => {
-                // If price_improvement_behavior is set to Ignore, we will not update our quotes based off the current
-                // market prices
+
+            if best_bid < fair_price_in_ticks.saturating_sub(phoenix_strategy.margin_in_ticks) {
+                bid_price_in_ticks = best_bid;
             }
         }
+        PriceImprovementBehavior::Join => {
+            // If price_improvement_behavior is set to Join, we will always join the best bid and ask
+            // if our quote prices are within the spread
+            ask_price_in_ticks = ask_price_in_ticks.max(best_ask);
+            bid_price_in_ticks = bid_price_in_ticks.min(best_bid);
+        }
+        PriceImprovementBehavior::Dime => {
+            // If price_improvement_behavior is set to Dime, we will never price improve by more than 1 tick
+            ask_price_in_ticks = ask_price_in_ticks.max(best_ask - 1);
+            bid_price_in_ticks = bid_price_in_ticks.min(best_bid + 1);
+        }
+        PriceImprovementBehavior::Ignore => {
+            // If price_improvement_behavior is set to Ignore, we will not update our quotes based off the current
+            // market prices
+        }
+    }
 
-        // Compute quote amounts in base lots
-        let size_in_quote_lots =
-            phoenix_strategy.quote_size_in_quote_atoms / header.get_quote_lot_size().as_u64();
+    // Compute quote amounts in base lots
+    let size_in_quote_lots =
+        phoenix_strategy.quote_size_in_quote_atoms / header.get_quote_lot_size().as_u64();
 
-        let bid_size_in_base_lots = size_in_quote_lots
-            * market.get_base_lots_per_base_unit().as_u64()
-            / (bid_price_in_ticks * market.get_tick_size().as_u64());
-        let ask_size_in_base_lots = size_in_quote_lots
-            * market.get_base_lots_per_base_unit().as_u64()
-            / (ask_price_in_ticks * market.get_tick_size().as_u64());
+    let mut bid_size_in_base_lots = size_in_quote_lots
+        * market.get_base_lots_per_base_unit().as_u64()
+        / (bid_price_in_ticks * market.get_tick_size().as_u64());
+    let mut ask_size_in_base_lots = size_in_quote_lots
+        * market.get_base_lots_per_base_unit().as_u64()
+        / (ask_price_in_ticks * market.get_tick_size().as_u64());
 
-        msg!(
-            "Our market: {} {} @ {} {}",
-            bid_size_in_base_lots,
+    // Never quote outside of the configured price band, resting orders on a refused side
+    // are cancelled
+    let bid_in_band = phoenix_strategy.is_price_in_band(bid_price_in_ticks);
+    let ask_in_band = phoenix_strategy.is_price_in_band(ask_price_in_ticks);
+    if !bid_in_band {
+        msg!("Bid price {} is outside of the price band", bid_price_in_ticks);
+    }
+    if !ask_in_band {
+        msg!("Ask price {} is outside of the price band", ask_price_in_ticks);
+    }
+
+    let mut update_bid = true;
+    let mut update_ask = true;
+    let orders_to_cancel = [
+        (
+            Side::Bid,
             bid_price_in_ticks,
+            bid_in_band,
+            FIFOOrderId::new_from_untyped(
+                phoenix_strategy.bid_price_in_ticks,
+                phoenix_strategy.bid_order_sequence_number,
+            ),
+            phoenix_strategy.initial_bid_size_in_base_lots,
+        ),
+        (
+            Side::Ask,
             ask_price_in_ticks,
-            ask_size_in_base_lots
-        );
-
-        let mut update_bid = true;
-        let mut update_ask = true;
-        let orders_to_cancel = [
-            (
-                Side::Bid,
-                bid_price_in_ticks,
-                FIFOOrderId::new_from_untyped(
-                    phoenix_strategy.bid_price_in_ticks,
-                    phoenix_strategy.bid_order_sequence_number,
-                ),
-                phoenix_strategy.initial_bid_size_in_base_lots,
+            ask_in_band,
+            FIFOOrderId::new_from_untyped(
+                phoenix_strategy.ask_price_in_ticks,
+                phoenix_strategy.ask_order_sequence_number,
             ),
-            (
-                Side::Ask,
-                ask_price_in_ticks,
-                FIFOOrderId::new_from_untyped(
-                    phoenix_strategy.ask_price_in_ticks,
-                    phoenix_strategy.ask_order_sequence_number,
-                ),
-                phoenix_strategy.initial_ask_size_in_base_lots,
+            phoenix_strategy.initial_ask_size_in_base_lots,
+        ),
+    ]
+    .iter()
+    .filter_map(|(side, price, in_band, order_id, initial_size)| {
+        if let Some(resting_order) = market.get_book(*side).get(order_id) {
+            // The order is 100% identical, do not cancel it
+            if resting_order.num_base_lots == *initial_size
+                && order_id.price_in_ticks.as_u64() == *price
+                && *in_band
+            {
+                match side {
+                    Side::Bid => update_bid = false,
+                    Side::Ask => update_ask = false,
+                }
+                return None;
+            }
+            // The order has been partially filled or reduced
+            return Some(*order_id);
+        }
+        // The order has been fully filled
+        None
+    })
+    .collect::<Vec<FIFOOrderId>>();
+
+    // Tracked orders that are not kept are either cancelled below or already off the book,
+    // clearing them makes sure their fills are only recorded once
+    if update_bid {
+        phoenix_strategy.clear_order(Side::Bid);
+    }
+    if update_ask {
+        phoenix_strategy.clear_order(Side::Ask);
+    }
+
+    // Don't update quotes if the price is invalid or if the sizes are 0
+    update_bid &= bid_price_in_ticks > 1 && bid_size_in_base_lots > 0;
+    update_ask &= ask_price_in_ticks < u64::MAX && ask_size_in_base_lots > 0;
+    update_bid &= bid_in_band;
+    update_ask &= ask_in_band;
+
+    // Enforce the cancel-replace budget, only placements count against it so stale orders
+    // are still cancelled once it is used up
+    if (update_bid || update_ask) && phoenix_strategy.max_cancel_replace_cycles > 0 {
+        if clock.slot
+            >= phoenix_strategy
+                .cancel_replace_window_start_slot
+                .saturating_add(phoenix_strategy.cancel_replace_window_in_slots)
+        {
+            phoenix_strategy.cancel_replace_window_start_slot = clock.slot;
+            phoenix_strategy.cancel_replace_cycles_in_window = 0;
+        }
+        if phoenix_strategy.is_cancel_replace_budget_exhausted(clock.slot) {
+            msg!("Cancel-replace budget exhausted, not placing orders");
+            update_bid = false;
+            update_ask = false;
+        } else {
+            phoenix_strategy.cancel_replace_cycles_in_window += 1;
+        }
+    }
+
+    if !update_ask && !update_bid && orders_to_cancel.is_empty() {
+        msg!("No orders to update");
+        return Ok(());
+    }
+
+    phoenix_strategy.num_cancel_replace_cycles += 1;
+
+    // Drop reference prior to invoking
+    drop(market_data);
+
+    // Cancel the old orders
+    if !orders_to_cancel.is_empty() {
+        invoke_signed(
+            &phoenix::program::create_cancel_multiple_orders_by_id_with_free_funds_instruction(
+                &market_account.key(),
+                &trader.key(),
+                &CancelMultipleOrdersByIdParams {
+                    orders: orders_to_cancel
+                        .iter()
+                        .map(|o_id| CancelOrderParams {
+                            order_sequence_number: o_id.order_sequence_number,
+                            price_in_ticks: o_id.price_in_ticks.as_u64(),
+                            side: Side::from_order_sequence_number(o_id.order_sequence_number),
+                        })
+                        .collect::<Vec<_>>(),
+                },
             ),
-        ]
-        .iter()
-        .filter_map(|(side, price, order_id, initial_size)| {
-            if let Some(resting_order) = market.get_book(*side).get(order_id) {
-                // The order is 100% identical, do not cancel it
-                if resting_order.num_base_lots == *initial_size
-                    && order_id.price_in_ticks.as_u64() == *price
+            &[
+                phoenix_program.to_account_info(),
+                log_authority.to_account_info(),
+                trader.to_account_info(),
+                market_account.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        emit!(OrdersCancelled {
+            strategy: strategy_key,
+            order_sequence_numbers: orders_to_cancel
+                .iter()
+                .map(|o_id| o_id.order_sequence_number)
+                .collect(),
+            cancelled_all: false,
+        });
+    }
+
+    // Shrink each side to what the trader can fund from the seat and the token accounts,
+    // so one underfunded side does not fail the whole update
+    if update_bid || update_ask {
+        let market_data = market_account.data.borrow();
+        let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
+        let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
+            .map_err(|_| {
+                msg!("Failed to deserialize market");
+                StrategyError::FailedToDeserializePhoenixMarket
+            })?
+            .inner;
+        let (quote_lots_free, base_lots_free) = get_trader_free_lots(market, &trader.key());
+        let quote_lot_size = header.get_quote_lot_size().as_u64();
+        let base_lot_size = header.get_base_lot_size().as_u64();
+        let available_quote_lots = (quote_lots_free * quote_lot_size
+            + token::accessor::amount(quote_account)?)
+        .saturating_sub(phoenix_strategy.quote_reserve_in_quote_atoms)
+            / quote_lot_size;
+        let available_base_lots = (base_lots_free * base_lot_size
+            + token::accessor::amount(base_account)?)
+        .saturating_sub(phoenix_strategy.base_reserve_in_base_atoms)
+            / base_lot_size;
+        let min_order_size_in_base_lots = phoenix_strategy.min_order_size_in_base_lots.max(1);
+
+        if update_bid {
+            let fundable_bid_size_in_base_lots = (available_quote_lots as u128
+                * market.get_base_lots_per_base_unit().as_u128()
+                / (bid_price_in_ticks as u128 * market.get_tick_size().as_u128()))
+                as u64;
+            if fundable_bid_size_in_base_lots < bid_size_in_base_lots {
+                msg!("Downsizing bid to {} base lots", fundable_bid_size_in_base_lots);
+                bid_size_in_base_lots = fundable_bid_size_in_base_lots;
+            }
+            if bid_size_in_base_lots < min_order_size_in_base_lots {
+                msg!("Not enough quote funds to place a bid");
+                update_bid = false;
+            }
+        }
+        if update_ask {
+            if available_base_lots < ask_size_in_base_lots {
+                msg!("Downsizing ask to {} base lots", available_base_lots);
+                ask_size_in_base_lots = available_base_lots;
+            }
+            if ask_size_in_base_lots < min_order_size_in_base_lots {
+                msg!("Not enough base funds to place an ask");
+                update_ask = false;
+            }
+        }
+
+        // Enforce the per order notional cap on the final sizes, like outside of the price
+        // band an order above the cap is not placed
+        if phoenix_strategy.max_order_notional_in_quote_atoms > 0 {
+            for (update, price_in_ticks, size_in_base_lots) in [
+                (&mut update_bid, bid_price_in_ticks, bid_size_in_base_lots),
+                (&mut update_ask, ask_price_in_ticks, ask_size_in_base_lots),
+            ] {
+                let notional_in_quote_atoms = get_order_notional_in_quote_atoms(
+                    price_in_ticks,
+                    size_in_base_lots,
+                    &header,
+                    market.get_base_lots_per_base_unit().as_u64(),
+                );
+                if *update
+                    && notional_in_quote_atoms > phoenix_strategy.max_order_notional_in_quote_atoms
                 {
-                    msg!("Resting order is identical: {:?}", order_id);
-                    match side {
-                        Side::Bid => update_bid = false,
-                        Side::Ask => update_ask = false,
-                    }
-                    return None;
+                    msg!(
+                        "Order notional of {} quote atoms is above the cap",
+                        notional_in_quote_atoms
+                    );
+                    *update = false;
                 }
-                msg!("Found partially filled resting order: {:?}", order_id);
-                // The order has been partially filled or reduced
-                return Some(*order_id);
             }
-            msg!("Failed to find resting order: {:?}", order_id);
-            // The order has been fully filled
-            None
-        })
-        .collect::<Vec<FIFOOrderId>>();
+        }
+    }
 
-        // Drop reference prior to invoking
-        drop(market_data);
+    // Limit orders can take from the book, what they took shows up in the trader's holdings
+    let holdings_before_placement = if !phoenix_strategy.post_only && (update_bid || update_ask) {
+        Some(get_trader_holdings(
+            market_account,
+            &header,
+            &trader.key(),
+            quote_account,
+            base_account,
+        )?)
+    } else {
+        None
+    };
 
-        // Cancel the old orders
-        if !orders_to_cancel.is_empty() {
-            invoke(
-                &phoenix::program::create_cancel_multiple_orders_by_id_with_free_funds_instruction(
+    let client_order_id = u128::from_le_bytes(trader.key().to_bytes()[..16].try_into().unwrap());
+    let mut order_ids = vec![];
+    if phoenix_strategy.post_only {
+        // Send multiple post-only orders in a single instruction
+        let multiple_order_packet = MultipleOrderPacket::new_with_failure_behavior(
+            if update_bid {
+                vec![CondensedOrder::new_default(
+                    bid_price_in_ticks,
+                    bid_size_in_base_lots,
+                )]
+            } else {
+                vec![]
+            },
+            if update_ask {
+                vec![CondensedOrder::new_default(
+                    ask_price_in_ticks,
+                    ask_size_in_base_lots,
+                )]
+            } else {
+                vec![]
+            },
+            Some(client_order_id),
+            if phoenix_strategy.fail_silently_on_insufficient_funds {
+                FailedMultipleLimitOrderBehavior::SkipOnInsufficientFundsAndAmendOnCross
+            } else {
+                FailedMultipleLimitOrderBehavior::FailOnInsufficientFundsAndAmendOnCross
+            },
+        );
+        invoke_signed(
+            &phoenix::program::create_new_multiple_order_instruction_with_custom_token_accounts(
+                &market_account.key(),
+                &trader.key(),
+                &base_account.key(),
+                &quote_account.key(),
+                &header.base_params.mint_key,
+                &header.quote_params.mint_key,
+                &multiple_order_packet,
+            ),
+            &[
+                phoenix_program.to_account_info(),
+                log_authority.to_account_info(),
+                trader.to_account_info(),
+                market_account.to_account_info(),
+                seat.to_account_info(),
+                quote_account.to_account_info(),
+                base_account.to_account_info(),
+                quote_vault.to_account_info(),
+                base_vault.to_account_info(),
+                token_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        parse_order_ids_from_return_data(&mut order_ids)?;
+    } else {
+        if update_bid {
+            invoke_signed(
+                &phoenix::program::create_new_order_instruction_with_custom_token_accounts(
                     &market_account.key(),
-                    &user.key(),
-                    &CancelMultipleOrdersByIdParams {
-                        orders: orders_to_cancel
-                            .iter()
-                            .map(|o_id| CancelOrderParams {
-                                order_sequence_number: o_id.order_sequence_number,
-                                price_in_ticks: o_id.price_in_ticks.as_u64(),
-                                side: Side::from_order_sequence_number(o_id.order_sequence_number),
-                            })
-                            .collect::<Vec<_>>(),
-                    },
+                    &trader.key(),
+                    &base_account.key(),
+                    &quote_account.key(),
+                    &header.base_params.mint_key,
+                    &header.quote_params.mint_key,
+                    &get_limit_order_packet(
+                        Side::Bid,
+                        bid_price_in_ticks,
+                        bid_size_in_base_lots,
+                        client_order_id,
+                        &phoenix_strategy,
+                    )?,
                 ),
                 &[
                     phoenix_program.to_account_info(),
                     log_authority.to_account_info(),
-                    user.to_account_info(),
+                    trader.to_account_info(),
                     market_account.to_account_info(),
+                    seat.to_account_info(),
+                    quote_account.to_account_info(),
+                    base_account.to_account_info(),
+                    quote_vault.to_account_info(),
+                    base_vault.to_account_info(),
+                    token_program.to_account_info(),
                 ],
+                signer_seeds,
             )?;
+            parse_order_ids_from_return_data(&mut order_ids)?;
         }
-
-        // Don't update quotes if the price is invalid or if the sizes are 0
-        update_bid &= bid_price_in_ticks > 1 && bid_size_in_base_lots > 0;
-        update_ask &= ask_price_in_ticks < u64::MAX && ask_size_in_base_lots > 0;
-
-        let client_order_id = u128::from_le_bytes(user.key().to_bytes()[..16].try_into().unwrap());
-        if !update_ask && !update_bid && orders_to_cancel.is_empty() {
-            msg!("No orders to update");
-            return Ok(());
-        }
-        let mut order_ids = vec![];
-        if phoenix_strategy.post_only
-            || !matches!(price_improvement_behavior, PriceImprovementBehavior::Join)
-        {
-            // Send multiple post-only orders in a single instruction
-            let multiple_order_packet = MultipleOrderPacket::new(
-                if update_bid {
-                    vec![CondensedOrder::new_default(
-                        bid_price_in_ticks,
-                        bid_size_in_base_lots,
-                    )]
-                } else {
-                    vec![]
-                },
-                if update_ask {
-                    vec![CondensedOrder::new_default(
-                        ask_price_in_ticks,
-                        ask_size_in_base_lots,
-                    )]
-                } else {
-                    vec![]
-                },
-                Some(client_order_id),
-                false,
-            );
-            invoke(
-                &phoenix::program::create_new_multiple_order_instruction_with_custom_token_accounts(
+        if update_ask {
+            invoke_signed(
+                &phoenix::program::create_new_order_instruction_with_custom_token_accounts(
                     &market_account.key(),
-                    &user.key(),
+                    &trader.key(),
                     &base_account.key(),
                     &quote_account.key(),
                     &header.base_params.mint_key,
                     &header.quote_params.mint_key,
-                    &multiple_order_packet,
+                    &get_limit_order_packet(
+                        Side::Ask,
+                        ask_price_in_ticks,
+                        ask_size_in_base_lots,
+                        client_order_id,
+                        &phoenix_strategy,
+                    )?,
                 ),
                 &[
                     phoenix_program.to_account_info(),
                     log_authority.to_account_info(),
-                    user.to_account_info(),
+                    trader.to_account_info(),
                     market_account.to_account_info(),
                     seat.to_account_info(),
                     quote_account.to_account_info(),
@@ -524,74 +1609,335 @@ pub mod uber_mm {
                     base_vault.to_account_info(),
                     token_program.to_account_info(),
                 ],
+                signer_seeds,
             )?;
             parse_order_ids_from_return_data(&mut order_ids)?;
-        } else {
-            if update_bid {
-                invoke(
-                    &phoenix::program::create_new_order_instruction_with_custom_token_accounts(
-                        &market_account.key(),
-                        &user.key(),
-                        &base_account.key(),
-                        &quote_account.key(),
-                        &header.base_params.mint_key,
-                        &header.quote_params.mint_key,
-                        &OrderPacket::new_limit_order_default_with_client_order_id(
-                            Side::Bid,
-                            bid_price_in_ticks,
-                            bid_size_in_base_lots,
-                            client_order_id,
-                        ),
-                    ),
-                    &[
-                        phoenix_program.to_account_info(),
-                        log_authority.to_account_info(),
-                        user.to_account_info(),
-                        market_account.to_account_info(),
-                        seat.to_account_info(),
-                        quote_account.to_account_info(),
-                        base_account.to_account_info(),
-                        quote_vault.to_account_info(),
-                        base_vault.to_account_info(),
-                        token_program.to_account_info(),
-                    ],
-                )?;
-                parse_order_ids_from_return_data(&mut order_ids)?;
+        }
+    }
+
+    let market_data = market_account.data.borrow();
+    let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
+    let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
+        .map_err(|_| {
+            msg!("Failed to deserialize market");
+            StrategyError::FailedToDeserializePhoenixMarket
+        })?
+        .inner;
+
+    let mut bid_resting_base_lots = 0;
+    let mut ask_resting_base_lots = 0;
+    for order_id in order_ids.iter() {
+        let side = Side::from_order_sequence_number(order_id.order_sequence_number);
+        match side {
+            Side::Ask => {
+                if let Some(order) = market.get_book(Side::Ask).get(order_id) {
+                    emit!(QuotePlaced {
+                        strategy: strategy_key,
+                        side: QuoteSide::Ask,
+                        price_in_ticks: order_id.price_in_ticks.as_u64(),
+                        size_in_base_lots: order.num_base_lots.as_u64(),
+                        order_sequence_number: order_id.order_sequence_number,
+                    });
+                    phoenix_strategy.ask_price_in_ticks = order_id.price_in_ticks.as_u64();
+                    phoenix_strategy.ask_order_sequence_number = order_id.order_sequence_number;
+                    phoenix_strategy.initial_ask_size_in_base_lots = order.num_base_lots.as_u64();
+                    ask_resting_base_lots = order.num_base_lots.as_u64();
+                }
             }
-            if update_ask {
-                invoke(
-                    &phoenix::program::create_new_order_instruction_with_custom_token_accounts(
-                        &market_account.key(),
-                        &user.key(),
-                        &base_account.key(),
-                        &quote_account.key(),
-                        &header.base_params.mint_key,
-                        &header.quote_params.mint_key,
-                        &OrderPacket::new_limit_order_default_with_client_order_id(
-                            Side::Ask,
-                            ask_price_in_ticks,
-                            ask_size_in_base_lots,
-                            client_order_id,
-                        ),
+            Side::Bid => {
+                if let Some(order) = market.get_book(Side::Bid).get(order_id) {
+                    emit!(QuotePlaced {
+                        strategy: strategy_key,
+                        side: QuoteSide::Bid,
+                        price_in_ticks: order_id.price_in_ticks.as_u64(),
+                        size_in_base_lots: order.num_base_lots.as_u64(),
+                        order_sequence_number: order_id.order_sequence_number,
+                    });
+                    phoenix_strategy.bid_price_in_ticks = order_id.price_in_ticks.as_u64();
+                    phoenix_strategy.bid_order_sequence_number = order_id.order_sequence_number;
+                    phoenix_strategy.initial_bid_size_in_base_lots = order.num_base_lots.as_u64();
+                    bid_resting_base_lots = order.num_base_lots.as_u64();
+                }
+            }
+        }
+    }
+    // An order that doesn't rest with its whole size either took from the book or was skipped
+    if let Some(holdings_before_placement) = holdings_before_placement {
+        if (update_bid && bid_resting_base_lots < bid_size_in_base_lots)
+            || (update_ask && ask_resting_base_lots < ask_size_in_base_lots)
+        {
+            let holdings_after_placement = get_trader_holdings(
+                market_account,
+                &header,
+                &trader.key(),
+                quote_account,
+                base_account,
+            )?;
+            record_placement_fill(
+                &strategy_key,
+                &mut phoenix_strategy,
+                &header,
+                market.get_base_lots_per_base_unit().as_u64(),
+                holdings_before_placement,
+                holdings_after_placement,
+            );
+        }
+    }
+    let (quote_lots_free, base_lots_free) = get_trader_free_lots(market, &trader.key());
+    phoenix_strategy.quote_lots_free_baseline = quote_lots_free as i64;
+    phoenix_strategy.base_lots_free_baseline = base_lots_free as i64;
+
+    Ok(())
+}
+
+#[program]
+pub mod uber_mm {
+    use super::*;
+
+    pub fn initialize(
+        ctx: Context<Initialize>, 
+        quote_edge_in_bps: u64, 
+        quote_size_in_quote_atoms: u64,
+        price_improvement_behavior: u8,
+        post_only: bool,
+        guardian: Option<Pubkey>,
+    ) -> Result<()> {
+        validate_quote_params(quote_edge_in_bps, quote_size_in_quote_atoms)?;
+        let price_improvement_behavior =
+            PriceImprovementBehavior::try_from(price_improvement_behavior)?;
+        load_header(&ctx.accounts.market)?;
+        let clock = Clock::get()?;
+        msg!("Initializing Phoenix Strategy");
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_init()?;
+        *phoenix_strategy = PhoenixStrategyState::new(
+            *ctx.accounts.user.key,
+            *ctx.accounts.user.key,
+            *ctx.accounts.market.key,
+            quote_edge_in_bps,
+            quote_size_in_quote_atoms,
+            price_improvement_behavior,
+            post_only,
+            guardian,
+            &clock,
+        );
+        emit!(StrategyInitialized {
+            strategy: ctx.accounts.phoenix_strategy.key(),
+            owner: ctx.accounts.user.key(),
+            trader: ctx.accounts.user.key(),
+            market: ctx.accounts.market.key(),
+        });
+        Ok(())
+    }
+
+    /// Creates a strategy whose Phoenix trader is the program derived trader authority and
+    /// requests its seat, the market authority still has to approve the seat
+    pub fn initialize_program_owned(
+        ctx: Context<InitializeProgramOwned>,
+        quote_edge_in_bps: u64,
+        quote_size_in_quote_atoms: u64,
+        price_improvement_behavior: u8,
+        post_only: bool,
+        guardian: Option<Pubkey>,
+    ) -> Result<()> {
+        let InitializeProgramOwned {
+            phoenix_strategy: phoenix_strategy_account,
+            user,
+            trader_authority,
+            seat_manager_program,
+            phoenix_program,
+            log_authority,
+            market,
+            market_config: _,
+            seat_manager,
+            seat_deposit_collector,
+            seat,
+            system_program,
+        } = ctx.accounts;
+
+        validate_quote_params(quote_edge_in_bps, quote_size_in_quote_atoms)?;
+        let price_improvement_behavior =
+            PriceImprovementBehavior::try_from(price_improvement_behavior)?;
+        load_header(market)?;
+        let clock = Clock::get()?;
+        msg!("Initializing program owned Phoenix Strategy");
+        {
+            let mut phoenix_strategy = phoenix_strategy_account.load_init()?;
+            *phoenix_strategy = PhoenixStrategyState::new(
+                user.key(),
+                trader_authority.key(),
+                market.key(),
+                quote_edge_in_bps,
+                quote_size_in_quote_atoms,
+                price_improvement_behavior,
+                post_only,
+                guardian,
+                &clock,
+            );
+        }
+
+        // The seat manager makes the trader pay for the seat and a deposit of two token
+        // account rents, so the trader authority is funded before it signs the claim
+        let rent = Rent::get()?;
+        let seat_cost = (rent.minimum_balance(std::mem::size_of::<phoenix::program::Seat>())
+            + 2 * rent.minimum_balance(token::TokenAccount::LEN))
+        .saturating_sub(trader_authority.lamports());
+        if seat_cost > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: user.to_account_info(),
+                        to: trader_authority.to_account_info(),
+                    },
+                ),
+                seat_cost,
+            )?;
+        }
+        let strategy_key = phoenix_strategy_account.key();
+        claim_seat(
+            seat_manager_program,
+            phoenix_program,
+            log_authority,
+            market,
+            seat_manager,
+            seat_deposit_collector,
+            trader_authority,
+            seat,
+            system_program,
+            &[&[
+                b"trader".as_ref(),
+                strategy_key.as_ref(),
+                &[*ctx.bumps.get("trader_authority").unwrap()],
+            ]],
+        )?;
+        msg!("Claimed seat {} for trader {}", seat.key(), trader_authority.key());
+        emit!(StrategyInitialized {
+            strategy: strategy_key,
+            owner: user.key(),
+            trader: trader_authority.key(),
+            market: market.key(),
+        });
+        Ok(())
+    }
+
+    /// Creates a strategy traded by the user's wallet and claims its seat through the
+    /// Phoenix seat manager in the same transaction
+    pub fn initialize_with_seat(
+        ctx: Context<InitializeWithSeat>,
+        quote_edge_in_bps: u64,
+        quote_size_in_quote_atoms: u64,
+        price_improvement_behavior: u8,
+        post_only: bool,
+        guardian: Option<Pubkey>,
+    ) -> Result<()> {
+        let InitializeWithSeat {
+            phoenix_strategy,
+            user,
+            seat_manager_program,
+            phoenix_program,
+            log_authority,
+            market,
+            market_config: _,
+            seat_manager,
+            seat_deposit_collector,
+            seat,
+            system_program,
+        } = ctx.accounts;
+
+        validate_quote_params(quote_edge_in_bps, quote_size_in_quote_atoms)?;
+        let price_improvement_behavior =
+            PriceImprovementBehavior::try_from(price_improvement_behavior)?;
+        load_header(market)?;
+        msg!("Initializing Phoenix Strategy");
+        *phoenix_strategy.load_init()? = PhoenixStrategyState::new(
+            user.key(),
+            user.key(),
+            market.key(),
+            quote_edge_in_bps,
+            quote_size_in_quote_atoms,
+            price_improvement_behavior,
+            post_only,
+            guardian,
+            &Clock::get()?,
+        );
+        claim_seat(
+            seat_manager_program,
+            phoenix_program,
+            log_authority,
+            market,
+            seat_manager,
+            seat_deposit_collector,
+            user,
+            seat,
+            system_program,
+            &[],
+        )?;
+        msg!("Claimed seat {} for trader {}", seat.key(), user.key());
+        emit!(StrategyInitialized {
+            strategy: phoenix_strategy.key(),
+            owner: user.key(),
+            trader: user.key(),
+            market: market.key(),
+        });
+        Ok(())
+    }
+
+    pub fn migrate_strategy(ctx: Context<MigrateStrategy>) -> Result<()> {
+        let MigrateStrategy {
+            phoenix_strategy,
+            user,
+            market,
+            system_program,
+        } = ctx.accounts;
+
+        require_keys_eq!(
+            *phoenix_strategy.owner,
+            crate::id(),
+            StrategyError::InvalidStrategyAccount
+        );
+        require!(
+            phoenix_strategy.try_borrow_data()?[..8] == PhoenixStrategyState::discriminator(),
+            StrategyError::InvalidStrategyAccount
+        );
+
+        let space = 8 + std::mem::size_of::<PhoenixStrategyState>();
+        if phoenix_strategy.data_len() < space {
+            msg!(
+                "Reallocating strategy from {} to {} bytes",
+                phoenix_strategy.data_len(),
+                space
+            );
+            let lamports_needed = Rent::get()?
+                .minimum_balance(space)
+                .saturating_sub(phoenix_strategy.lamports());
+            if lamports_needed > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: user.to_account_info(),
+                            to: phoenix_strategy.to_account_info(),
+                        },
                     ),
-                    &[
-                        phoenix_program.to_account_info(),
-                        log_authority.to_account_info(),
-                        user.to_account_info(),
-                        market_account.to_account_info(),
-                        seat.to_account_info(),
-                        quote_account.to_account_info(),
-                        base_account.to_account_info(),
-                        quote_vault.to_account_info(),
-                        base_vault.to_account_info(),
-                        token_program.to_account_info(),
-                    ],
+                    lamports_needed,
                 )?;
-                parse_order_ids_from_return_data(&mut order_ids)?;
             }
+            phoenix_strategy.realloc(space, true)?;
         }
 
-        let market_data = market_account.data.borrow();
+        let phoenix_strategy = AccountLoader::<PhoenixStrategyState>::try_from(phoenix_strategy)?;
+        let mut phoenix_strategy = phoenix_strategy.load_mut()?;
+        if phoenix_strategy.version == PHOENIX_STRATEGY_VERSION {
+            msg!("Strategy is already at version {}", PHOENIX_STRATEGY_VERSION);
+            return Ok(());
+        }
+        msg!(
+            "Migrating strategy from version {} to {}",
+            phoenix_strategy.version,
+            PHOENIX_STRATEGY_VERSION
+        );
+        let header = load_header(market)?;
+        let market_data = market.data.borrow();
         let (_, market_bytes) = market_data.split_at(std::mem::size_of::<MarketHeader>());
         let market = phoenix::program::load_with_dispatch(&header.market_size_params, market_bytes)
             .map_err(|_| {
@@ -599,96 +1945,2067 @@ pub mod uber_mm {
                 StrategyError::FailedToDeserializePhoenixMarket
             })?
             .inner;
+        let trader_state = market
+            .get_trader_state(&phoenix_strategy.trader)
+            .copied()
+            .unwrap_or_default();
+        phoenix_strategy.migrate(
+            Clock::get()?.slot,
+            &trader_state,
+            &header,
+            market.get_base_lots_per_base_unit().as_u64(),
+        )
+    }
 
-        for order_id in order_ids.iter() {
-            let side = Side::from_order_sequence_number(order_id.order_sequence_number);
-            match side {
-                Side::Ask => {
-                    market
-                        .get_book(Side::Ask)
-                        .get(&order_id)
-                        .map(|order| {
-                            msg!("Placed Ask Order: {:?}", order_id);
-                            phoenix_strategy.ask_price_in_ticks = order_id.price_in_ticks.as_u64();
-                            phoenix_strategy.ask_order_sequence_number =
-                                order_id.order_sequence_number;
-                            phoenix_strategy.initial_ask_size_in_base_lots =
-                                order.num_base_lots.as_u64();
-                        })
-                        .unwrap_or_else(|| {
-                            msg!("Ask order not found");
-                        });
-                }
-                Side::Bid => {
-                    market
-                        .get_book(Side::Bid)
-                        .get(&order_id)
-                        .map(|order| {
-                            msg!("Placed Bid Order: {:?}", order_id);
-                            phoenix_strategy.bid_price_in_ticks = order_id.price_in_ticks.as_u64();
-                            phoenix_strategy.bid_order_sequence_number =
-                                order_id.order_sequence_number;
-                            phoenix_strategy.initial_bid_size_in_base_lots =
-                                order.num_base_lots.as_u64();
-                        })
-                        .unwrap_or_else(|| {
-                            msg!("Bid order not found");
-                        });
-                }
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        let Pause {
+            phoenix_strategy: phoenix_strategy_account,
+            authority,
+            owner: _,
+            trader,
+            phoenix_program,
+            log_authority,
+            market,
+        } = ctx.accounts;
+
+        let mut phoenix_strategy = phoenix_strategy_account.load_mut()?;
+        require!(
+            phoenix_strategy.can_pause(authority.key),
+            StrategyError::InvalidPauseAuthority
+        );
+        msg!("Pausing Phoenix Strategy");
+        phoenix_strategy.paused = true;
+
+        // Phoenix only lets the trader cancel its own orders, so a guardian pausing a
+        // wallet owned strategy on its own can stop new quotes but cannot pull the resting ones
+        let strategy_key = phoenix_strategy_account.key();
+        match get_trader_authority_bump(&strategy_key, trader) {
+            Ok(trader_authority_bump) => {
+                let bump_seed = [trader_authority_bump.unwrap_or_default()];
+                let trader_seeds: &[&[u8]] =
+                    &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+                let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+                    &[trader_seeds]
+                } else {
+                    &[]
+                };
+                record_fills_before_cancel(&strategy_key, &mut phoenix_strategy, market)?;
+                cancel_all_orders(phoenix_program, log_authority, trader, market, signer_seeds)?;
+                phoenix_strategy.clear_orders();
+                emit!(OrdersCancelled {
+                    strategy: strategy_key,
+                    order_sequence_numbers: vec![],
+                    cancelled_all: true,
+                });
             }
+            Err(_) => msg!("Trader did not sign, resting orders were not cancelled"),
         }
+        Ok(())
+    }
 
+    pub fn resume(ctx: Context<ManageStrategy>) -> Result<()> {
+        msg!("Resuming Phoenix Strategy");
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_mut()?;
+        phoenix_strategy.paused = false;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
-        bump,
-        payer = user,
-        space = 8 + std::mem::size_of::<PhoenixStrategyState>(),
-    )]
-    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    /// CHECK: Checked in instruction
-    pub market: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn cancel_if_stale(ctx: Context<CancelIfStale>) -> Result<()> {
+        let CancelIfStale {
+            phoenix_strategy: phoenix_strategy_account,
+            trader_authority,
+            phoenix_program,
+            log_authority,
+            market,
+        } = ctx.accounts;
 
-#[derive(Accounts)]
-pub struct UpdateQuotes<'info> {
-    #[account(
-        mut,
-        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
-        bump,
-    )]
-    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
-    pub user: Signer<'info>,
-    pub phoenix_program: Program<'info, PhoenixV1>,
-    /// CHECK: Checked in CPI
-    pub log_authority: UncheckedAccount<'info>,
-    /// CHECK: Checked in instruction and CPI
-    #[account(mut)]
-    pub market: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
-    pub seat: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
-    #[account(mut)]
-    pub quote_account: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
-    #[account(mut)]
-    pub base_account: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
-    #[account(mut)]
-    pub quote_vault: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
-    #[account(mut)]
-    pub base_vault: UncheckedAccount<'info>,
-    /// CHECK: Checked in CPI
+        let mut phoenix_strategy = phoenix_strategy_account.load_mut()?;
+        require!(
+            phoenix_strategy.trader == trader_authority.key(),
+            StrategyError::TraderNotProgramOwned
+        );
+        let clock = Clock::get()?;
+        require!(
+            phoenix_strategy.is_stale(clock.slot),
+            StrategyError::StrategyNotStale
+        );
+        msg!(
+            "Strategy was last updated at slot {}, cancelling all orders",
+            phoenix_strategy.last_update_slot
+        );
+
+        let strategy_key = phoenix_strategy_account.key();
+        record_fills_before_cancel(&strategy_key, &mut phoenix_strategy, market)?;
+        cancel_all_orders(
+            phoenix_program,
+            log_authority,
+            trader_authority,
+            market,
+            &[&[
+                b"trader".as_ref(),
+                strategy_key.as_ref(),
+                &[*ctx.bumps.get("trader_authority").unwrap()],
+            ]],
+        )?;
+        phoenix_strategy.clear_orders();
+        emit!(OrdersCancelled {
+            strategy: strategy_key,
+            order_sequence_numbers: vec![],
+            cancelled_all: true,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_all_quotes(ctx: Context<CancelAllQuotes>) -> Result<()> {
+        let CancelAllQuotes {
+            phoenix_strategy: phoenix_strategy_account,
+            authority,
+            trader,
+            phoenix_program,
+            log_authority,
+            market,
+        } = ctx.accounts;
+
+        let mut phoenix_strategy = phoenix_strategy_account.load_mut()?;
+        require!(
+            phoenix_strategy.can_operate(authority.key),
+            StrategyError::InvalidStrategyAuthority
+        );
+        let strategy_key = phoenix_strategy_account.key();
+        let trader_authority_bump = get_trader_authority_bump(&strategy_key, trader)?;
+        let bump_seed = [trader_authority_bump.unwrap_or_default()];
+        let trader_seeds: &[&[u8]] = &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+        let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+            &[trader_seeds]
+        } else {
+            &[]
+        };
+        record_fills_before_cancel(&strategy_key, &mut phoenix_strategy, market)?;
+        cancel_all_orders(phoenix_program, log_authority, trader, market, signer_seeds)?;
+        phoenix_strategy.clear_orders();
+        emit!(OrdersCancelled {
+            strategy: strategy_key,
+            order_sequence_numbers: vec![],
+            cancelled_all: true,
+        });
+        Ok(())
+    }
+
+    /// Moves funds from the trader's token accounts into the Phoenix seat,
+    /// None deposits the whole token account balance
+    pub fn deposit_to_seat(
+        ctx: Context<ManageSeatFunds>,
+        quote_lots: Option<u64>,
+        base_lots: Option<u64>,
+    ) -> Result<()> {
+        let ManageSeatFunds {
+            phoenix_strategy,
+            user: _,
+            trader,
+            phoenix_program,
+            log_authority,
+            market,
+            seat,
+            quote_account,
+            base_account,
+            quote_vault,
+            base_vault,
+            token_program,
+        } = ctx.accounts;
+
+        let strategy_key = phoenix_strategy.key();
+        let trader_authority_bump = get_trader_authority_bump(&strategy_key, trader)?;
+        let bump_seed = [trader_authority_bump.unwrap_or_default()];
+        let trader_seeds: &[&[u8]] = &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+        let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+            &[trader_seeds]
+        } else {
+            &[]
+        };
+        let header = load_header(market)?;
+        let quote_lots_to_deposit = match quote_lots {
+            Some(quote_lots) => quote_lots,
+            None => token::accessor::amount(quote_account)? / header.get_quote_lot_size().as_u64(),
+        };
+        let base_lots_to_deposit = match base_lots {
+            Some(base_lots) => base_lots,
+            None => token::accessor::amount(base_account)? / header.get_base_lot_size().as_u64(),
+        };
+        msg!(
+            "Depositing {} quote lots and {} base lots",
+            quote_lots_to_deposit,
+            base_lots_to_deposit
+        );
+        let trader_state_before = load_trader_state(market, &header, trader.key)?;
+        invoke_signed(
+            &phoenix::program::create_deposit_funds_instruction_with_custom_token_accounts(
+                &market.key(),
+                &trader.key(),
+                &seat.key(),
+                &base_account.key(),
+                &quote_account.key(),
+                &header.base_params.mint_key,
+                &header.quote_params.mint_key,
+                &DepositParams {
+                    quote_lots_to_deposit,
+                    base_lots_to_deposit,
+                },
+            ),
+            &[
+                phoenix_program.to_account_info(),
+                log_authority.to_account_info(),
+                trader.to_account_info(),
+                market.to_account_info(),
+                seat.to_account_info(),
+                quote_account.to_account_info(),
+                base_account.to_account_info(),
+                quote_vault.to_account_info(),
+                base_vault.to_account_info(),
+                token_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        let trader_state_after = load_trader_state(market, &header, trader.key)?;
+        phoenix_strategy
+            .load_mut()?
+            .shift_free_lots_baseline(&trader_state_before, &trader_state_after);
+        Ok(())
+    }
+
+    /// Moves free funds from the Phoenix seat to the trader's token accounts,
+    /// None withdraws everything that is not locked in orders
+    pub fn withdraw_from_seat(
+        ctx: Context<ManageSeatFunds>,
+        quote_lots: Option<u64>,
+        base_lots: Option<u64>,
+    ) -> Result<()> {
+        let ManageSeatFunds {
+            phoenix_strategy,
+            user: _,
+            trader,
+            phoenix_program,
+            log_authority,
+            market,
+            seat: _,
+            quote_account,
+            base_account,
+            quote_vault,
+            base_vault,
+            token_program,
+        } = ctx.accounts;
+
+        let strategy_key = phoenix_strategy.key();
+        let trader_authority_bump = get_trader_authority_bump(&strategy_key, trader)?;
+        let bump_seed = [trader_authority_bump.unwrap_or_default()];
+        let trader_seeds: &[&[u8]] = &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+        let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+            &[trader_seeds]
+        } else {
+            &[]
+        };
+        let header = load_header(market)?;
+        let trader_state_before = load_trader_state(market, &header, trader.key)?;
+        invoke_signed(
+            &phoenix::program::create_withdraw_funds_with_custom_amounts_instruction_with_custom_token_accounts(
+                &market.key(),
+                &trader.key(),
+                &base_account.key(),
+                &quote_account.key(),
+                &header.base_params.mint_key,
+                &header.quote_params.mint_key,
+                &WithdrawParams {
+                    quote_lots_to_withdraw: quote_lots,
+                    base_lots_to_withdraw: base_lots,
+                },
+            ),
+            &[
+                phoenix_program.to_account_info(),
+                log_authority.to_account_info(),
+                trader.to_account_info(),
+                market.to_account_info(),
+                quote_account.to_account_info(),
+                base_account.to_account_info(),
+                quote_vault.to_account_info(),
+                base_vault.to_account_info(),
+                token_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        let trader_state_after = load_trader_state(market, &header, trader.key)?;
+        phoenix_strategy
+            .load_mut()?
+            .shift_free_lots_baseline(&trader_state_before, &trader_state_after);
+        Ok(())
+    }
+
+    pub fn close_strategy(ctx: Context<CloseStrategy>) -> Result<()> {
+        let CloseStrategy {
+            phoenix_strategy,
+            user: _,
+            vault,
+            history,
+            portfolio,
+            trader,
+            phoenix_program,
+            log_authority,
+            market,
+            quote_account,
+            base_account,
+            quote_vault,
+            base_vault,
+            token_program,
+        } = ctx.accounts;
+
+        let strategy_key = phoenix_strategy.key();
+        let trader_authority_bump = get_trader_authority_bump(&strategy_key, trader)?;
+        let bump_seed = [trader_authority_bump.unwrap_or_default()];
+        let trader_seeds: &[&[u8]] = &[b"trader".as_ref(), strategy_key.as_ref(), &bump_seed];
+        let signer_seeds: &[&[&[u8]]] = if trader_authority_bump.is_some() {
+            &[trader_seeds]
+        } else {
+            &[]
+        };
+        require!(vault.data_is_empty(), StrategyError::StrategyHasVault);
+        require!(history.data_is_empty(), StrategyError::StrategyHasHistory);
+        // A closed strategy left in the portfolio would fail every update_portfolio
+        if !portfolio.data_is_empty() {
+            let portfolio = AccountLoader::<Portfolio>::try_from(portfolio)?;
+            let mut portfolio = portfolio.load_mut()?;
+            if portfolio.strategies().contains(&strategy_key) {
+                msg!("Removing strategy {} from the portfolio", strategy_key);
+                portfolio.remove_strategy(&strategy_key)?;
+            }
+        }
+        let header = load_header(market)?;
+        msg!("Closing Phoenix Strategy");
+        cancel_all_orders(phoenix_program, log_authority, trader, market, signer_seeds)?;
+        invoke_signed(
+            &phoenix::program::create_withdraw_funds_instruction_with_custom_token_accounts(
+                &market.key(),
+                &trader.key(),
+                &base_account.key(),
+                &quote_account.key(),
+                &header.base_params.mint_key,
+                &header.quote_params.mint_key,
+            ),
+            &[
+                phoenix_program.to_account_info(),
+                log_authority.to_account_info(),
+                trader.to_account_info(),
+                market.to_account_info(),
+                quote_account.to_account_info(),
+                base_account.to_account_info(),
+                quote_vault.to_account_info(),
+                base_vault.to_account_info(),
+                token_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        Ok(())
+    }
+
+    /// Moves tokens out of a token account owned by the trader authority to the owner,
+    /// None transfers the whole balance
+    pub fn withdraw_trader_funds(
+        ctx: Context<WithdrawTraderFunds>,
+        amount: Option<u64>,
+    ) -> Result<()> {
+        let WithdrawTraderFunds {
+            phoenix_strategy,
+            user: _,
+            market: _,
+            vault,
+            trader_authority,
+            source_account,
+            destination_account,
+            token_program,
+        } = ctx.accounts;
+
+        require!(vault.data_is_empty(), StrategyError::StrategyHasVault);
+        let amount = match amount {
+            Some(amount) => amount,
+            None => token::accessor::amount(source_account)?,
+        };
+        msg!("Withdrawing {} atoms from the trader authority", amount);
+        let strategy_key = phoenix_strategy.key();
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: source_account.to_account_info(),
+                    to: destination_account.to_account_info(),
+                    authority: trader_authority.to_account_info(),
+                },
+                &[&[
+                    b"trader".as_ref(),
+                    strategy_key.as_ref(),
+                    &[*ctx.bumps.get("trader_authority").unwrap()],
+                ]],
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    /// Creates the LP vault of a program owned strategy, along with its share mint and the
+    /// trader authority token accounts that hold the pooled funds
+    pub fn initialize_vault(
+        ctx: Context<InitializeVault>,
+        withdrawal_queue_enabled: bool,
+        fee_recipient: Pubkey,
+        performance_fee_in_bps: u64,
+        management_fee_in_bps: u64,
+    ) -> Result<()> {
+        let InitializeVault {
+            vault,
+            phoenix_strategy,
+            user: _,
+            market,
+            market_config,
+            trader_authority: _,
+            share_mint,
+            quote_mint,
+            base_mint,
+            quote_account,
+            base_account,
+            base_oracle,
+            quote_oracle,
+            token_program: _,
+            system_program: _,
+            rent: _,
+        } = ctx.accounts;
+
+        let header = load_header(market)?;
+        require_keys_eq!(
+            quote_mint.key(),
+            header.quote_params.mint_key,
+            StrategyError::InvalidVaultMint
+        );
+        require_keys_eq!(
+            base_mint.key(),
+            header.base_params.mint_key,
+            StrategyError::InvalidVaultMint
+        );
+        require!(
+            performance_fee_in_bps < 10000 && management_fee_in_bps < 10000,
+            StrategyError::InvalidVaultFees
+        );
+        // Make sure the registered feeds can price the vault before anyone deposits
+        market_config
+            .load()?
+            .load_prices(&phoenix_strategy.key(), base_oracle, quote_oracle)?;
+        msg!("Initializing vault for strategy {}", phoenix_strategy.key());
+        phoenix_strategy.load_mut()?.has_vault = true;
+        *vault.load_init()? = StrategyVault::new(
+            phoenix_strategy.key(),
+            share_mint.key(),
+            quote_account.key(),
+            base_account.key(),
+            withdrawal_queue_enabled,
+            fee_recipient,
+            performance_fee_in_bps,
+            management_fee_in_bps,
+            Clock::get()?.unix_timestamp,
+        );
+        Ok(())
+    }
+
+    /// Mints the management and performance fees accrued since the last crystallization
+    /// to the fee recipient, anyone can crank it
+    pub fn crystallize_fees(ctx: Context<CrystallizeFees>) -> Result<()> {
+        ctx.accounts.crystallize()?;
+        Ok(())
+    }
+
+    /// Deposits into the vault and mints shares priced at the vault NAV,
+    /// pending fees are crystallized first so the depositor doesn't pay for them
+    pub fn deposit_to_vault(
+        ctx: Context<DepositToVault>,
+        quote_atoms: u64,
+        base_atoms: u64,
+    ) -> Result<()> {
+        let DepositToVault {
+            fees,
+            lp,
+            lp_quote_account,
+            lp_base_account,
+            lp_share_account,
+        } = ctx.accounts;
+
+        let (nav, total_shares, base_price, quote_price) = fees.crystallize()?;
+        let header = load_header(&fees.market)?;
+        let deposit_value =
+            get_value_in_quote_atoms(quote_atoms, base_atoms, base_price, quote_price, &header);
+        let shares = get_shares_for_deposit(
+            deposit_value,
+            nav,
+            total_shares,
+            get_vault_virtual_offset(&header),
+        )?;
+        require!(shares > 0, StrategyError::InvalidVaultAmount);
+        msg!(
+            "Vault NAV is {} quote atoms, depositing {} for {} shares",
+            nav,
+            deposit_value,
+            shares
+        );
+
+        for (from, to, amount) in [
+            (lp_quote_account, &fees.quote_account, quote_atoms),
+            (lp_base_account, &fees.base_account, base_atoms),
+        ] {
+            if amount > 0 {
+                token::transfer(
+                    CpiContext::new(
+                        fees.token_program.to_account_info(),
+                        token::Transfer {
+                            from: from.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: lp.to_account_info(),
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        fees.mint_shares(lp_share_account, shares)
+    }
+
+    /// Burns shares and pays out their share of the vault funds, pending fees are
+    /// crystallized first so the LP pays them too, fails while the strategy has orders resting
+    pub fn withdraw_from_vault(ctx: Context<WithdrawFromVault>, shares: u64) -> Result<()> {
+        let WithdrawFromVault {
+            payout,
+            lp,
+            lp_share_account,
+        } = ctx.accounts;
+
+        require!(shares > 0, StrategyError::InvalidVaultAmount);
+        let (nav, total_shares, _, _) = payout.fees.crystallize()?;
+        token::burn(
+            CpiContext::new(
+                payout.fees.token_program.to_account_info(),
+                token::Burn {
+                    mint: payout.fees.share_mint.to_account_info(),
+                    from: lp_share_account.to_account_info(),
+                    authority: lp.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        payout.pay_out(shares, nav, total_shares)
+    }
+
+    /// Burns shares now and records a withdrawal that is paid out by process_withdrawal
+    /// once the strategy has no orders resting
+    pub fn queue_withdrawal(ctx: Context<QueueWithdrawal>, shares: u64) -> Result<()> {
+        let QueueWithdrawal {
+            vault: vault_account,
+            withdrawal_request,
+            share_mint,
+            lp,
+            lp_share_account,
+            token_program,
+            system_program: _,
+        } = ctx.accounts;
+
+        let mut vault = vault_account.load_mut()?;
+        require!(
+            vault.withdrawal_queue_enabled,
+            StrategyError::WithdrawalQueueDisabled
+        );
+        require!(shares > 0, StrategyError::InvalidVaultAmount);
+        token::burn(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token::Burn {
+                    mint: share_mint.to_account_info(),
+                    from: lp_share_account.to_account_info(),
+                    authority: lp.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        vault.queued_shares += shares;
+        msg!("Queued withdrawal of {} shares", shares);
+        *withdrawal_request.load_init()? = WithdrawalRequest {
+            vault: vault_account.key(),
+            lp: lp.key(),
+            shares,
+            queued_slot: Clock::get()?.slot,
+        };
+        Ok(())
+    }
+
+    /// Pays out a queued withdrawal, anyone can crank it once no orders are resting
+    pub fn process_withdrawal(ctx: Context<ProcessWithdrawal>) -> Result<()> {
+        let ProcessWithdrawal {
+            payout,
+            withdrawal_request,
+            lp,
+        } = ctx.accounts;
+
+        for lp_account in [&payout.lp_quote_account, &payout.lp_base_account] {
+            require_keys_eq!(
+                token::accessor::authority(lp_account)?,
+                lp.key(),
+                StrategyError::InvalidVaultTokenAccount
+            );
+        }
+        let shares = withdrawal_request.load()?.shares;
+        let (nav, total_shares, _, _) = payout.fees.crystallize()?;
+        payout.pay_out(shares, nav, total_shares)?;
+        payout.fees.vault.load_mut()?.queued_shares -= shares;
+        Ok(())
+    }
+
+    pub fn set_strategy_params(
+        ctx: Context<ManageStrategy>,
+        params: StrategyParams,
+    ) -> Result<()> {
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_mut()?;
+        validate_quote_params(
+            params
+                .quote_edge_in_bps
+                .unwrap_or(phoenix_strategy.quote_edge_in_bps),
+            params
+                .quote_size_in_quote_atoms
+                .unwrap_or(phoenix_strategy.quote_size_in_quote_atoms),
+        )?;
+        if let Some(quote_edge_in_bps) = params.quote_edge_in_bps {
+            phoenix_strategy.quote_edge_in_bps = quote_edge_in_bps;
+        }
+        if let Some(quote_size_in_quote_atoms) = params.quote_size_in_quote_atoms {
+            phoenix_strategy.quote_size_in_quote_atoms = quote_size_in_quote_atoms;
+        }
+        if let Some(price_improvement_behavior) = params.price_improvement_behavior {
+            phoenix_strategy.price_improvement_behavior = price_improvement_behavior.to_u8();
+        }
+        if let Some(post_only) = params.post_only {
+            phoenix_strategy.post_only = post_only;
+        }
+        if let Some(margin_in_ticks) = params.margin_in_ticks {
+            phoenix_strategy.margin_in_ticks = margin_in_ticks;
+        }
+        emit!(StrategyParamsChanged {
+            strategy: ctx.accounts.phoenix_strategy.key(),
+            params,
+        });
+        Ok(())
+    }
+
+    pub fn set_operator(ctx: Context<ManageStrategy>, operator: Option<Pubkey>) -> Result<()> {
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_mut()?;
+        phoenix_strategy.operator = operator.unwrap_or_default();
+        emit!(OperatorChanged {
+            strategy: ctx.accounts.phoenix_strategy.key(),
+            operator,
+        });
+        Ok(())
+    }
+
+    pub fn set_risk_params(ctx: Context<ManageStrategy>, params: RiskParams) -> Result<()> {
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_mut()?;
+        if let Some(max_book_deviation_in_bps) = params.max_book_deviation_in_bps {
+            phoenix_strategy.max_book_deviation_in_bps = max_book_deviation_in_bps;
+        }
+        if let Some(min_slots_between_updates) = params.min_slots_between_updates {
+            phoenix_strategy.min_slots_between_updates = min_slots_between_updates;
+        }
+        if let Some(max_order_notional) = params.max_order_notional_in_quote_atoms {
+            phoenix_strategy.max_order_notional_in_quote_atoms = max_order_notional;
+        }
+        if let Some(max_cancel_replace_cycles) = params.max_cancel_replace_cycles {
+            phoenix_strategy.max_cancel_replace_cycles = max_cancel_replace_cycles;
+        }
+        if let Some(window_in_slots) = params.cancel_replace_window_in_slots {
+            phoenix_strategy.cancel_replace_window_in_slots = window_in_slots;
+        }
+        if let Some(min_price_in_ticks) = params.min_quote_price_in_ticks {
+            phoenix_strategy.min_quote_price_in_ticks = min_price_in_ticks;
+        }
+        if let Some(max_price_in_ticks) = params.max_quote_price_in_ticks {
+            phoenix_strategy.max_quote_price_in_ticks = max_price_in_ticks;
+        }
+        if let Some(heartbeat_in_slots) = params.heartbeat_in_slots {
+            phoenix_strategy.heartbeat_in_slots = heartbeat_in_slots;
+        }
+        require!(
+            phoenix_strategy.max_cancel_replace_cycles == 0
+                || phoenix_strategy.cancel_replace_window_in_slots > 0,
+            StrategyError::InvalidStrategyParams
+        );
+        require!(
+            phoenix_strategy.max_quote_price_in_ticks == 0
+                || phoenix_strategy.min_quote_price_in_ticks
+                    <= phoenix_strategy.max_quote_price_in_ticks,
+            StrategyError::InvalidStrategyParams
+        );
+        emit!(RiskParamsChanged {
+            strategy: ctx.accounts.phoenix_strategy.key(),
+            params,
+        });
+        Ok(())
+    }
+
+    pub fn set_execution_params(
+        ctx: Context<ManageStrategy>,
+        params: ExecutionParams,
+    ) -> Result<()> {
+        let mut phoenix_strategy = ctx.accounts.phoenix_strategy.load_mut()?;
+        if let Some(self_trade_behavior) = params.self_trade_behavior {
+            phoenix_strategy.self_trade_behavior = self_trade_behavior.to_u8();
+        }
+        if let Some(fail_silently) = params.fail_silently_on_insufficient_funds {
+            phoenix_strategy.fail_silently_on_insufficient_funds = fail_silently;
+        }
+        if let Some(quote_reserve) = params.quote_reserve_in_quote_atoms {
+            phoenix_strategy.quote_reserve_in_quote_atoms = quote_reserve;
+        }
+        if let Some(base_reserve) = params.base_reserve_in_base_atoms {
+            phoenix_strategy.base_reserve_in_base_atoms = base_reserve;
+        }
+        if let Some(min_order_size) = params.min_order_size_in_base_lots {
+            phoenix_strategy.min_order_size_in_base_lots = min_order_size;
+        }
+        emit!(ExecutionParamsChanged {
+            strategy: ctx.accounts.phoenix_strategy.key(),
+            params,
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts are the registered base and quote oracles of the market, optionally
+    /// followed by the strategy history account
+    /// Only the owner can override the fair price
+    pub fn update_quotes<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateQuotes<'info>>,
+        fair_price_in_quote_atoms_per_raw_base_unit: Option<u64>,
+    ) -> Result<()> {
+        let oracle_prices = check_strategy_quotes(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            fair_price_in_quote_atoms_per_raw_base_unit,
+        )?;
+        update_strategy_quotes(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            fair_price_in_quote_atoms_per_raw_base_unit,
+            oracle_prices,
+        )
+    }
+
+    /// Creates the optional history account of a strategy, update_quotes appends a record to
+    /// it when it is passed after the oracles
+    pub fn initialize_history(ctx: Context<InitializeHistory>) -> Result<()> {
+        msg!("Initializing history for strategy {}", ctx.accounts.phoenix_strategy.key());
+        ctx.accounts.history.load_init()?.phoenix_strategy = ctx.accounts.phoenix_strategy.key();
+        Ok(())
+    }
+
+    /// Has to run before close_strategy, the owner is checked against the strategy
+    pub fn close_history(_ctx: Context<CloseHistory>) -> Result<()> {
+        msg!("Closing strategy history");
+        Ok(())
+    }
+
+    /// Returns the strategy PnL through the return data, unrealized PnL is as of the last
+    /// update_quotes that used the oracle
+    pub fn get_pnl(ctx: Context<GetPnl>) -> Result<StrategyPnl> {
+        let phoenix_strategy = ctx.accounts.phoenix_strategy.load()?;
+        Ok(StrategyPnl {
+            realized_pnl_in_quote_atoms: phoenix_strategy.realized_pnl_in_quote_atoms,
+            unrealized_pnl_in_quote_atoms: phoenix_strategy.unrealized_pnl_in_quote_atoms,
+            net_position_in_base_lots: phoenix_strategy.net_position_in_base_lots,
+            average_entry_price_in_ticks: phoenix_strategy.average_entry_price_in_ticks,
+            last_mark_price_in_ticks: phoenix_strategy.last_mark_price_in_ticks,
+        })
+    }
+
+    /// Creates the global config, only the program upgrade authority can become the first admin
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        msg!("Initializing global config with admin {}", ctx.accounts.admin.key());
+        ctx.accounts.config.load_init()?.admin = ctx.accounts.admin.key();
+        Ok(())
+    }
+
+    pub fn set_config_admin(ctx: Context<ManageConfig>, admin: Pubkey) -> Result<()> {
+        msg!("Setting config admin to {}", admin);
+        ctx.accounts.config.load_mut()?.admin = admin;
+        Ok(())
+    }
+
+    /// Adds a Phoenix market to the registry, strategies can only be created for
+    /// registered markets
+    pub fn register_market(ctx: Context<RegisterMarket>, oracle_type: OracleType) -> Result<()> {
+        let RegisterMarket {
+            market_config,
+            config: _,
+            admin: _,
+            market,
+            base_oracle,
+            quote_oracle,
+            system_program: _,
+        } = ctx.accounts;
+
+        load_header(market)?;
+        let new_config = MarketConfig::new(
+            market.key(),
+            base_oracle.key(),
+            quote_oracle.key(),
+            oracle_type,
+        );
+        new_config.load_prices(&Pubkey::default(), base_oracle, quote_oracle)?;
+        msg!("Registering market {}", market.key());
+        *market_config.load_init()? = new_config;
+        Ok(())
+    }
+
+    /// Replaces the oracles of a registered market and enables or disables it, quotes
+    /// can't be updated on a disabled market
+    pub fn update_market_config(
+        ctx: Context<UpdateMarketConfig>,
+        oracle_type: OracleType,
+        enabled: bool,
+    ) -> Result<()> {
+        let UpdateMarketConfig {
+            market_config: market_config_account,
+            config: _,
+            admin: _,
+            base_oracle,
+            quote_oracle,
+        } = ctx.accounts;
+
+        let mut market_config = market_config_account.load_mut()?;
+        market_config.base_oracle = base_oracle.key();
+        market_config.quote_oracle = quote_oracle.key();
+        market_config.oracle_type = oracle_type.to_u8();
+        // A market is disabled when its feeds break, so only enabling needs live prices
+        if enabled {
+            market_config.load_prices(&Pubkey::default(), base_oracle, quote_oracle)?;
+        }
+        market_config.enabled = enabled;
+        msg!("Market {} enabled: {}", market_config.market, enabled);
+        Ok(())
+    }
+
+    pub fn initialize_portfolio(ctx: Context<InitializePortfolio>) -> Result<()> {
+        msg!("Initializing portfolio");
+        *ctx.accounts.portfolio.load_init()? = Portfolio {
+            owner: ctx.accounts.user.key(),
+            strategies: [Pubkey::default(); MAX_PORTFOLIO_STRATEGIES],
+            num_strategies: 0,
+        };
+        Ok(())
+    }
+
+    pub fn add_to_portfolio(ctx: Context<AddToPortfolio>) -> Result<()> {
+        let strategy = ctx.accounts.phoenix_strategy.key();
+        msg!("Adding strategy {} to the portfolio", strategy);
+        ctx.accounts.portfolio.load_mut()?.add_strategy(strategy)
+    }
+
+    pub fn remove_from_portfolio(ctx: Context<ManagePortfolio>, strategy: Pubkey) -> Result<()> {
+        msg!("Removing strategy {} from the portfolio", strategy);
+        ctx.accounts.portfolio.load_mut()?.remove_strategy(&strategy)
+    }
+
+    /// Updates the quotes of every strategy in the portfolio in one transaction. Remaining
+    /// accounts hold one group per strategy in portfolio order, each group being the
+    /// update_quotes accounts followed by the registered base and quote oracles and optionally
+    /// the strategy history account. Overrides are either empty or one per strategy.
+    /// Strategies that can't quote right now, e.g. paused, rate limited or with a rejected
+    /// oracle, are logged and skipped. Closed strategies are removed from the portfolio by
+    /// close_strategy, but a failing Phoenix CPI or a group with invalid accounts still fails
+    /// the whole transaction
+    pub fn update_portfolio<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePortfolio<'info>>,
+        fair_price_overrides: Vec<Option<u64>>,
+    ) -> Result<()> {
+        let portfolio = ctx.accounts.portfolio.load()?;
+        let strategies = portfolio.strategies();
+        require!(
+            fair_price_overrides.is_empty() || fair_price_overrides.len() == strategies.len(),
+            StrategyError::InvalidPortfolioAccounts
+        );
+
+        let mut remaining_accounts = ctx.remaining_accounts;
+        for (i, strategy_key) in strategies.iter().enumerate() {
+            // Each group goes through the same account checks as update_quotes
+            let mut accounts = UpdateQuotes::try_accounts(
+                ctx.program_id,
+                &mut remaining_accounts,
+                &[],
+                &mut BTreeMap::new(),
+                &mut BTreeSet::new(),
+            )?;
+            require_keys_eq!(
+                accounts.phoenix_strategy.key(),
+                *strategy_key,
+                StrategyError::InvalidPortfolioAccounts
+            );
+            require!(
+                remaining_accounts.len() >= 2,
+                StrategyError::InvalidPortfolioAccounts
+            );
+            // The history account can't be mistaken for the first account of the next group
+            let has_history = matches!(
+                remaining_accounts.get(2),
+                Some(account) if AccountLoader::<StrategyHistory>::try_from(account).is_ok()
+            );
+            let (oracle_accounts, rest) =
+                remaining_accounts.split_at(if has_history { 3 } else { 2 });
+            remaining_accounts = rest;
+            let fair_price_override = fair_price_overrides.get(i).copied().flatten();
+            // One strategy that can't quote right now must not hold back the others
+            let oracle_prices = match check_strategy_quotes(
+                &accounts,
+                oracle_accounts,
+                fair_price_override,
+            ) {
+                Ok(oracle_prices) => oracle_prices,
+                Err(error) => {
+                    msg!("Skipping strategy {}: {}", strategy_key, error);
+                    continue;
+                }
+            };
+            msg!("Updating strategy {}", strategy_key);
+            update_strategy_quotes(
+                &mut accounts,
+                oracle_accounts,
+                fair_price_override,
+                oracle_prices,
+            )?;
+            accounts.exit(ctx.program_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<PhoenixStrategyState>(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Checked in instruction
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+        constraint = market_config.load()?.enabled @ StrategyError::MarketDisabled,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramOwned<'info> {
+    #[account(
+        init,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<PhoenixStrategyState>(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Program derived trader of the strategy, pays for the seat
+    #[account(
+        mut,
+        seeds=[b"trader".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub trader_authority: UncheckedAccount<'info>,
+    pub seat_manager_program: Program<'info, PhoenixSeatManager>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+        constraint = market_config.load()?.enabled @ StrategyError::MarketDisabled,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat_manager: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat_deposit_collector: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWithSeat<'info> {
+    #[account(
+        init,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<PhoenixStrategyState>(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub seat_manager_program: Program<'info, PhoenixSeatManager>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+        constraint = market_config.load()?.enabled @ StrategyError::MarketDisabled,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat_manager: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat_deposit_collector: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub seat: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateQuotes<'info> {
+    #[account(
+        mut,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.market == market.key(),
+        constraint = phoenix_strategy.load()?.trader == trader.key(),
+        constraint = phoenix_strategy.load()?.seat == seat.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    /// Either the owner or the strategy operator
+    pub authority: Signer<'info>,
+    /// CHECK: Checked against the strategy, signs the CPIs or is the program derived trader authority
+    pub trader: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+        constraint = market_config.load()?.enabled @ StrategyError::MarketDisabled,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    /// CHECK: Checked against the strategy
+    pub seat: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateStrategy<'info> {
+    /// CHECK: Older layouts can't be loaded, checked in instruction
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+    )]
+    pub phoenix_strategy: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Checked by the strategy seeds
+    pub market: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), owner.key.as_ref(), market.key.as_ref()],
+        bump,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.trader == trader.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    /// Either the owner or the strategy guardian
+    pub authority: Signer<'info>,
+    /// CHECK: Checked by the strategy seeds
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: Checked against the strategy, must sign or be the program derived trader
+    /// authority for the orders to be cancelled
+    pub trader: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageStrategy<'info> {
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    pub user: Signer<'info>,
+    /// CHECK: Checked by the strategy seeds
+    pub market: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelIfStale<'info> {
+    #[account(
+        mut,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.market == market.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    /// CHECK: Checked against the strategy trader in instruction
+    #[account(
+        seeds=[b"trader".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub trader_authority: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAllQuotes<'info> {
+    #[account(
+        mut,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.market == market.key(),
+        constraint = phoenix_strategy.load()?.trader == trader.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    /// Either the owner or the strategy operator
+    pub authority: Signer<'info>,
+    /// CHECK: Checked against the strategy, signs the CPI or is the program derived trader authority
+    pub trader: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageSeatFunds<'info> {
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.trader == trader.key(),
+        constraint = phoenix_strategy.load()?.seat == seat.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    pub user: Signer<'info>,
+    /// CHECK: Checked against the strategy, signs the CPI or is the program derived trader authority
+    pub trader: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Checked against the strategy
+    pub seat: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStrategy<'info> {
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        close = user,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.trader == trader.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Must be empty, strategies with LP funds can't be closed
+    #[account(
+        seeds=[b"vault".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Must be empty, close_history has to run first so the rent isn't left behind
+    #[account(
+        seeds=[b"history".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub history: UncheckedAccount<'info>,
+    /// CHECK: Portfolio of the owner if it exists, checked in instruction
+    #[account(
+        mut,
+        seeds=[b"portfolio".as_ref(), user.key.as_ref()],
+        bump,
+    )]
+    pub portfolio: UncheckedAccount<'info>,
+    /// CHECK: Checked against the strategy, signs the CPIs or is the program derived trader authority
+    pub trader: UncheckedAccount<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
     pub token_program: UncheckedAccount<'info>,
 }
 
+#[derive(Accounts)]
+pub struct WithdrawTraderFunds<'info> {
+    /// CHECK: Only used to derive the trader authority, so funds can be recovered after the
+    /// strategy is closed
+    #[account(
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+    )]
+    pub phoenix_strategy: UncheckedAccount<'info>,
+    pub user: Signer<'info>,
+    /// CHECK: Checked by the strategy seeds
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Must be empty, LP funds can only leave through the vault
+    #[account(
+        seeds=[b"vault".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Program derived trader of the strategy, owns the source account
+    #[account(
+        seeds=[b"trader".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+    )]
+    pub trader_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub source_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub destination_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, token::Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        seeds=[b"vault".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<StrategyVault>(),
+    )]
+    pub vault: AccountLoader<'info, StrategyVault>,
+    #[account(
+        mut,
+        seeds=[b"phoenix".as_ref(), user.key.as_ref(), market.key.as_ref()],
+        bump,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Checked in instruction
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    /// CHECK: Program derived trader of the strategy, owns the vault funds and mints the shares
+    #[account(
+        seeds=[b"trader".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        constraint = phoenix_strategy.load()?.trader == trader_authority.key()
+            @ StrategyError::TraderNotProgramOwned,
+    )]
+    pub trader_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        seeds=[b"shares".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        payer = user,
+        mint::decimals = quote_mint.decimals,
+        mint::authority = trader_authority,
+    )]
+    pub share_mint: Box<Account<'info, token::Mint>>,
+    pub quote_mint: Box<Account<'info, token::Mint>>,
+    pub base_mint: Box<Account<'info, token::Mint>>,
+    #[account(
+        init,
+        seeds=[b"quote".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        payer = user,
+        token::mint = quote_mint,
+        token::authority = trader_authority,
+    )]
+    pub quote_account: Box<Account<'info, token::TokenAccount>>,
+    #[account(
+        init,
+        seeds=[b"base".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        payer = user,
+        token::mint = base_mint,
+        token::authority = trader_authority,
+    )]
+    pub base_account: Box<Account<'info, token::TokenAccount>>,
+    /// CHECK: Checked against the market config
+    pub base_oracle: UncheckedAccount<'info>,
+    /// CHECK: Checked against the market config
+    pub quote_oracle: UncheckedAccount<'info>,
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CrystallizeFees<'info> {
+    #[account(
+        mut,
+        constraint = vault.load()?.phoenix_strategy == phoenix_strategy.key(),
+        constraint = vault.load()?.share_mint == share_mint.key(),
+        constraint = vault.load()?.quote_account == quote_account.key(),
+        constraint = vault.load()?.base_account == base_account.key(),
+    )]
+    pub vault: AccountLoader<'info, StrategyVault>,
+    #[account(
+        mut,
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.market == market.key(),
+        constraint = phoenix_strategy.load()?.trader == trader_authority.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    /// CHECK: Checked against the strategy, program derived trader authority that mints the shares
+    pub trader_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction and CPI
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub share_mint: Box<Account<'info, token::Mint>>,
+    /// CHECK: Share token account of the vault fee recipient, checked in instruction
+    #[account(mut)]
+    pub fee_recipient_account: UncheckedAccount<'info>,
+    /// CHECK: Checked against the vault
+    #[account(mut)]
+    pub quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked against the vault
+    #[account(mut)]
+    pub base_account: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    /// CHECK: Checked against the market config
+    pub base_oracle: UncheckedAccount<'info>,
+    /// CHECK: Checked against the market config
+    pub quote_oracle: UncheckedAccount<'info>,
+    pub token_program: Program<'info, token::Token>,
+}
+
+impl<'info> CrystallizeFees<'info> {
+    /// Mints the accrued fees to the fee recipient and returns the NAV, the total shares
+    /// after the fees and the scaled base and quote prices
+    fn crystallize(&self) -> Result<(u64, u64, u128, u128)> {
+        require_keys_eq!(
+            token::accessor::authority(&self.fee_recipient_account)?,
+            self.vault.load()?.fee_recipient,
+            StrategyError::InvalidVaultTokenAccount
+        );
+        let header = load_header(&self.market)?;
+        let (quote_atoms, base_atoms) = get_trader_holdings(
+            &self.market,
+            &header,
+            self.trader_authority.key,
+            &self.quote_account,
+            &self.base_account,
+        )?;
+        let (base_price, quote_price) = self
+            .market_config
+            .load()?
+            .load_prices(
+                &self.phoenix_strategy.key(),
+                &self.base_oracle,
+                &self.quote_oracle,
+            )?;
+        let nav = get_value_in_quote_atoms(quote_atoms, base_atoms, base_price, quote_price, &header);
+
+        let mut vault = self.vault.load_mut()?;
+        let total_shares = vault.get_total_shares(self.share_mint.supply);
+        let fee_shares = vault.accrue_fees(nav, total_shares, Clock::get()?.unix_timestamp)?;
+        drop(vault);
+        if fee_shares > 0 {
+            msg!("Minting {} fee shares at a NAV of {} quote atoms", fee_shares, nav);
+            self.mint_shares(&self.fee_recipient_account, fee_shares)?;
+        }
+        Ok((nav, total_shares + fee_shares, base_price, quote_price))
+    }
+
+    fn mint_shares(&self, to: &AccountInfo<'info>, shares: u64) -> Result<()> {
+        let strategy_key = self.phoenix_strategy.key();
+        let (_, bump) = get_trader_authority_address(&strategy_key);
+        token::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::MintTo {
+                    mint: self.share_mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.trader_authority.to_account_info(),
+                },
+                &[&[b"trader".as_ref(), strategy_key.as_ref(), &[bump]]],
+            ),
+            shares,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct DepositToVault<'info> {
+    pub fees: CrystallizeFees<'info>,
+    pub lp: Signer<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_base_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_share_account: UncheckedAccount<'info>,
+}
+
+/// Accounts needed to pay out vault shares to an LP, fees are crystallized before every payout
+#[derive(Accounts)]
+pub struct VaultPayout<'info> {
+    pub fees: CrystallizeFees<'info>,
+    pub phoenix_program: Program<'info, PhoenixV1>,
+    /// CHECK: Checked in CPI
+    pub log_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_quote_account: UncheckedAccount<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_base_account: UncheckedAccount<'info>,
+}
+
+impl<'info> VaultPayout<'info> {
+    /// Pulls the free seat funds into the vault token accounts and transfers the pro-rata
+    /// share of them to the LP, funds locked in orders can't be paid out
+    fn pay_out(&self, shares: u64, nav: u64, total_shares: u64) -> Result<()> {
+        let fees = &self.fees;
+        let header = load_header(&fees.market)?;
+        let trader_state = load_trader_state(&fees.market, &header, fees.trader_authority.key)?;
+        require!(
+            trader_state.quote_lots_locked.as_u64() == 0 && trader_state.base_lots_locked.as_u64() == 0,
+            StrategyError::VaultOrdersResting
+        );
+
+        let strategy_key = fees.phoenix_strategy.key();
+        // Nothing rests, the tracked orders filled or were cancelled and the proceeds of their
+        // fills are about to leave the seat
+        let mut phoenix_strategy = fees.phoenix_strategy.load_mut()?;
+        record_fills_before_cancel(&strategy_key, &mut phoenix_strategy, &fees.market)?;
+        phoenix_strategy.clear_orders();
+        drop(phoenix_strategy);
+
+        let (_, bump) = get_trader_authority_address(&strategy_key);
+        let signer_seeds: &[&[&[u8]]] = &[&[b"trader".as_ref(), strategy_key.as_ref(), &[bump]]];
+        if trader_state.quote_lots_free.as_u64() > 0 || trader_state.base_lots_free.as_u64() > 0 {
+            invoke_signed(
+                &phoenix::program::create_withdraw_funds_instruction_with_custom_token_accounts(
+                    &fees.market.key(),
+                    &fees.trader_authority.key(),
+                    &fees.base_account.key(),
+                    &fees.quote_account.key(),
+                    &header.base_params.mint_key,
+                    &header.quote_params.mint_key,
+                ),
+                &[
+                    self.phoenix_program.to_account_info(),
+                    self.log_authority.to_account_info(),
+                    fees.trader_authority.to_account_info(),
+                    fees.market.to_account_info(),
+                    fees.quote_account.to_account_info(),
+                    fees.base_account.to_account_info(),
+                    self.quote_vault.to_account_info(),
+                    self.base_vault.to_account_info(),
+                    fees.token_program.to_account_info(),
+                ],
+                signer_seeds,
+            )?;
+        }
+
+        let share_value =
+            get_share_value(shares, nav, total_shares, get_vault_virtual_offset(&header));
+        let quote_atoms =
+            get_pro_rata_amount(token::accessor::amount(&fees.quote_account)?, share_value, nav);
+        let base_atoms =
+            get_pro_rata_amount(token::accessor::amount(&fees.base_account)?, share_value, nav);
+        msg!(
+            "Paying out {} quote atoms and {} base atoms for {} shares",
+            quote_atoms,
+            base_atoms,
+            shares
+        );
+        for (from, to, amount) in [
+            (&fees.quote_account, &self.lp_quote_account, quote_atoms),
+            (&fees.base_account, &self.lp_base_account, base_atoms),
+        ] {
+            if amount > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        fees.token_program.to_account_info(),
+                        token::Transfer {
+                            from: from.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: fees.trader_authority.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromVault<'info> {
+    pub payout: VaultPayout<'info>,
+    pub lp: Signer<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_share_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueWithdrawal<'info> {
+    #[account(
+        mut,
+        constraint = vault.load()?.share_mint == share_mint.key(),
+    )]
+    pub vault: AccountLoader<'info, StrategyVault>,
+    #[account(
+        init,
+        seeds=[b"withdrawal".as_ref(), vault.key().as_ref(), lp.key.as_ref()],
+        bump,
+        payer = lp,
+        space = 8 + std::mem::size_of::<WithdrawalRequest>(),
+    )]
+    pub withdrawal_request: AccountLoader<'info, WithdrawalRequest>,
+    #[account(mut)]
+    pub share_mint: Box<Account<'info, token::Mint>>,
+    #[account(mut)]
+    pub lp: Signer<'info>,
+    /// CHECK: Checked in CPI
+    #[account(mut)]
+    pub lp_share_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessWithdrawal<'info> {
+    pub payout: VaultPayout<'info>,
+    #[account(
+        mut,
+        seeds=[b"withdrawal".as_ref(), payout.fees.vault.key().as_ref(), lp.key.as_ref()],
+        bump,
+        close = lp,
+    )]
+    pub withdrawal_request: AccountLoader<'info, WithdrawalRequest>,
+    /// CHECK: Checked by the withdrawal request seeds, receives the request rent
+    #[account(mut)]
+    pub lp: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePortfolio<'info> {
+    #[account(
+        init,
+        seeds=[b"portfolio".as_ref(), user.key.as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<Portfolio>(),
+    )]
+    pub portfolio: AccountLoader<'info, Portfolio>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddToPortfolio<'info> {
+    #[account(
+        mut,
+        seeds=[b"portfolio".as_ref(), user.key.as_ref()],
+        bump,
+    )]
+    pub portfolio: AccountLoader<'info, Portfolio>,
+    pub user: Signer<'info>,
+    #[account(
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.owner == user.key(),
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+}
+
+#[derive(Accounts)]
+pub struct ManagePortfolio<'info> {
+    #[account(
+        mut,
+        seeds=[b"portfolio".as_ref(), user.key.as_ref()],
+        bump,
+    )]
+    pub portfolio: AccountLoader<'info, Portfolio>,
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePortfolio<'info> {
+    pub portfolio: AccountLoader<'info, Portfolio>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeHistory<'info> {
+    #[account(
+        init,
+        seeds=[b"history".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + std::mem::size_of::<StrategyHistory>(),
+    )]
+    pub history: AccountLoader<'info, StrategyHistory>,
+    #[account(
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.owner == user.key()
+            @ StrategyError::InvalidStrategyAuthority,
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseHistory<'info> {
+    #[account(
+        mut,
+        seeds=[b"history".as_ref(), phoenix_strategy.key().as_ref()],
+        bump,
+        close = user,
+    )]
+    pub history: AccountLoader<'info, StrategyHistory>,
+    #[account(
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+        constraint = phoenix_strategy.load()?.owner == user.key()
+            @ StrategyError::InvalidStrategyAuthority,
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetPnl<'info> {
+    #[account(
+        constraint = is_strategy_layout_current(phoenix_strategy.as_ref())
+            @ StrategyError::StrategyNeedsMigration,
+    )]
+    pub phoenix_strategy: AccountLoader<'info, PhoenixStrategyState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds=[b"config".as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + std::mem::size_of::<GlobalConfig>(),
+    )]
+    pub config: AccountLoader<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()),
+    )]
+    pub program: Program<'info, crate::program::UberMm>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ StrategyError::InvalidConfigAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageConfig<'info> {
+    #[account(
+        mut,
+        seeds=[b"config".as_ref()],
+        bump,
+        constraint = config.load()?.admin == admin.key() @ StrategyError::InvalidConfigAdmin,
+    )]
+    pub config: AccountLoader<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMarket<'info> {
+    #[account(
+        init,
+        seeds=[b"market".as_ref(), market.key.as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + std::mem::size_of::<MarketConfig>(),
+    )]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    #[account(
+        seeds=[b"config".as_ref()],
+        bump,
+        constraint = config.load()?.admin == admin.key() @ StrategyError::InvalidConfigAdmin,
+    )]
+    pub config: AccountLoader<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Checked in instruction
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction
+    pub base_oracle: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction
+    pub quote_oracle: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketConfig<'info> {
+    #[account(mut)]
+    pub market_config: AccountLoader<'info, MarketConfig>,
+    #[account(
+        seeds=[b"config".as_ref()],
+        bump,
+        constraint = config.load()?.admin == admin.key() @ StrategyError::InvalidConfigAdmin,
+    )]
+    pub config: AccountLoader<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+    /// CHECK: Checked in instruction
+    pub base_oracle: UncheckedAccount<'info>,
+    /// CHECK: Checked in instruction
+    pub quote_oracle: UncheckedAccount<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phoenix::quantities::{
+        BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot,
+    };
+
+    #[test]
+    fn price_improvement_behavior_bytes() {
+        for byte in 0..=3 {
+            assert_eq!(PriceImprovementBehavior::try_from(byte).unwrap().to_u8(), byte);
+        }
+        for byte in 4..=u8::MAX {
+            assert_eq!(
+                PriceImprovementBehavior::try_from(byte).unwrap_err(),
+                error!(StrategyError::InvalidPriceImprovementBehavior)
+            );
+        }
+    }
+
+    #[test]
+    fn self_trade_behavior_bytes() {
+        for byte in 0..=2 {
+            assert_eq!(SelfTradeBehavior::try_from(byte).unwrap().to_u8(), byte);
+        }
+        for byte in 3..=u8::MAX {
+            assert_eq!(
+                SelfTradeBehavior::try_from(byte).unwrap_err(),
+                error!(StrategyError::InvalidSelfTradeBehavior)
+            );
+        }
+    }
+
+    #[test]
+    fn oracle_type_bytes() {
+        assert_eq!(OracleType::try_from(0).unwrap().to_u8(), 0);
+        for byte in 1..=u8::MAX {
+            assert_eq!(
+                OracleType::try_from(byte).unwrap_err(),
+                error!(StrategyError::InvalidOracleType)
+            );
+        }
+    }
+
+    // 100 quote atoms per tick per base unit and 10 base lots per base unit, so one tick on
+    // one base lot is worth 10 quote atoms
+    const BASE_LOTS_PER_BASE_UNIT: u64 = 10;
+
+    fn test_header() -> MarketHeader {
+        MarketHeader::new(
+            bytemuck::Zeroable::zeroed(),
+            bytemuck::Zeroable::zeroed(),
+            BaseAtomsPerBaseLot::new(1000),
+            bytemuck::Zeroable::zeroed(),
+            QuoteAtomsPerQuoteLot::new(1),
+            QuoteAtomsPerBaseUnitPerTick::new(100),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            1,
+        )
+    }
+
+    fn fill(strategy: &mut PhoenixStrategyState, side: Side, price_in_ticks: u64, base_lots: u64) {
+        let header = test_header();
+        let quote_atoms = get_order_notional_in_quote_atoms(
+            price_in_ticks,
+            base_lots,
+            &header,
+            BASE_LOTS_PER_BASE_UNIT,
+        );
+        strategy.update_cost_basis(
+            side,
+            base_lots,
+            quote_atoms,
+            &header,
+            BASE_LOTS_PER_BASE_UNIT,
+        );
+    }
+
+    #[test]
+    fn cost_basis_partial_close_of_long() {
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        fill(&mut strategy, Side::Bid, 100, 10);
+        fill(&mut strategy, Side::Bid, 110, 10);
+        assert_eq!(strategy.net_position_in_base_lots, 20);
+        assert_eq!(strategy.average_entry_price_in_ticks, 105);
+
+        // Closing part of the position realizes PnL and keeps the entry price
+        fill(&mut strategy, Side::Ask, 120, 5);
+        assert_eq!(strategy.net_position_in_base_lots, 15);
+        assert_eq!(strategy.average_entry_price_in_ticks, 105);
+        assert_eq!(strategy.realized_pnl_in_quote_atoms, 750);
+    }
+
+    #[test]
+    fn cost_basis_flip_from_long_to_short() {
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        fill(&mut strategy, Side::Bid, 105, 15);
+        fill(&mut strategy, Side::Ask, 100, 25);
+        assert_eq!(strategy.realized_pnl_in_quote_atoms, -750);
+        // The rest of the fill opens the short at the fill price
+        assert_eq!(strategy.net_position_in_base_lots, -10);
+        assert_eq!(strategy.average_entry_price_in_ticks, 100);
+    }
+
+    #[test]
+    fn cost_basis_short_until_flat() {
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        fill(&mut strategy, Side::Ask, 100, 10);
+        fill(&mut strategy, Side::Ask, 90, 10);
+        assert_eq!(strategy.net_position_in_base_lots, -20);
+        assert_eq!(strategy.average_entry_price_in_ticks, 95);
+
+        fill(&mut strategy, Side::Bid, 85, 5);
+        assert_eq!(strategy.net_position_in_base_lots, -15);
+        assert_eq!(strategy.average_entry_price_in_ticks, 95);
+        assert_eq!(strategy.realized_pnl_in_quote_atoms, 500);
+
+        fill(&mut strategy, Side::Bid, 100, 15);
+        assert_eq!(strategy.net_position_in_base_lots, 0);
+        assert_eq!(strategy.average_entry_price_in_ticks, 0);
+        assert_eq!(strategy.realized_pnl_in_quote_atoms, -250);
+    }
+
+    #[test]
+    fn mark_to_price_long_short_and_flat() {
+        let header = test_header();
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        fill(&mut strategy, Side::Bid, 100, 10);
+        strategy.mark_to_price(110, &header, BASE_LOTS_PER_BASE_UNIT);
+        assert_eq!(strategy.unrealized_pnl_in_quote_atoms, 1000);
+        assert_eq!(strategy.last_mark_price_in_ticks, 110);
+
+        fill(&mut strategy, Side::Ask, 100, 20);
+        strategy.mark_to_price(110, &header, BASE_LOTS_PER_BASE_UNIT);
+        assert_eq!(strategy.unrealized_pnl_in_quote_atoms, -1000);
+
+        fill(&mut strategy, Side::Bid, 100, 10);
+        strategy.mark_to_price(90, &header, BASE_LOTS_PER_BASE_UNIT);
+        assert_eq!(strategy.unrealized_pnl_in_quote_atoms, 0);
+        assert_eq!(strategy.last_mark_price_in_ticks, 90);
+    }
+
+    #[test]
+    fn bid_partly_crossing_at_placement() {
+        let header = test_header();
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        // A 10 lot bid at 100 takes 4 lots for 4_000 quote atoms plus a 4 atom taker fee, the
+        // rest rests with its quote locked in the seat
+        record_placement_fill(
+            &Pubkey::default(),
+            &mut strategy,
+            &header,
+            BASE_LOTS_PER_BASE_UNIT,
+            (100_000, 0),
+            (95_996, 4_000),
+        );
+        assert_eq!(strategy.num_bid_fills, 1);
+        assert_eq!(strategy.base_lots_bought, 4);
+        assert_eq!(strategy.quote_atoms_paid, 4_004);
+        assert_eq!(strategy.net_position_in_base_lots, 4);
+        assert_eq!(strategy.position_cost_in_quote_atoms, 4_004);
+        assert_eq!(strategy.average_entry_price_in_ticks, 100);
+
+        // The fee shows up as a loss at the entry price
+        strategy.mark_to_price(100, &header, BASE_LOTS_PER_BASE_UNIT);
+        assert_eq!(strategy.unrealized_pnl_in_quote_atoms, -4);
+
+        // The resting part fills without fees and the whole position is sold
+        fill(&mut strategy, Side::Bid, 100, 6);
+        fill(&mut strategy, Side::Ask, 101, 10);
+        assert_eq!(strategy.net_position_in_base_lots, 0);
+        assert_eq!(strategy.position_cost_in_quote_atoms, 0);
+        assert_eq!(strategy.realized_pnl_in_quote_atoms, 96);
+    }
+
+    #[test]
+    fn nothing_taken_at_placement_is_not_booked() {
+        let mut strategy: PhoenixStrategyState = bytemuck::Zeroable::zeroed();
+        record_placement_fill(
+            &Pubkey::default(),
+            &mut strategy,
+            &test_header(),
+            BASE_LOTS_PER_BASE_UNIT,
+            (100_000, 5_000),
+            (100_000, 5_000),
+        );
+        assert_eq!(strategy.num_bid_fills + strategy.num_ask_fills, 0);
+        assert_eq!(strategy.net_position_in_base_lots, 0);
+    }
+
+    #[test]
+    fn quote_params_validation() {
+        assert!(validate_quote_params(1, 1).is_ok());
+        assert!(validate_quote_params(9_999, 1).is_ok());
+        assert_eq!(
+            validate_quote_params(0, 1).unwrap_err(),
+            error!(StrategyError::EdgeMustBeNonZero)
+        );
+        assert_eq!(
+            validate_quote_params(10_000, 1).unwrap_err(),
+            error!(StrategyError::InvalidStrategyParams)
+        );
+        assert_eq!(
+            validate_quote_params(100, 0).unwrap_err(),
+            error!(StrategyError::InvalidStrategyParams)
+        );
+    }
+
+    #[test]
+    fn book_deviation_uses_the_furthest_side() {
+        assert_eq!(get_book_deviation_in_bps(100, 99, 102), 200);
+        assert_eq!(get_book_deviation_in_bps(100, 97, 102), 300);
+    }
+
+    #[test]
+    fn book_deviation_ignores_empty_sides() {
+        assert_eq!(get_book_deviation_in_bps(100, 1, 102), 200);
+        assert_eq!(get_book_deviation_in_bps(100, 97, u64::MAX), 300);
+        assert_eq!(get_book_deviation_in_bps(100, 1, u64::MAX), 0);
+        // Without a fair price every book deviates
+        assert_eq!(get_book_deviation_in_bps(0, 99, 102), u64::MAX);
+    }
+}
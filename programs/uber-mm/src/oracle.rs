@@ -126,11 +126,12 @@ pub struct SimplePriceInfo {
 pub struct SimplePrice {
     pub expo: i32,                      // Price exponent
     pub price: i64,                     // Aggregate price info
+    pub conf: u64,                      // Aggregate confidence interval
 }
 
 impl Price {
     #[inline]
-    pub fn load<'a>(price_feed: &'a AccountInfo) -> Result<SimplePrice> {
+    pub fn load<'a>(price_feed: &'a AccountInfo, strategy: &Pubkey) -> Result<SimplePrice> {
         let account_data: RefMut<'a, [u8]>;
         let state: RefMut<'a, Self>;
 
@@ -140,26 +141,57 @@ impl Price {
             from_bytes_mut(cast_slice_mut::<u8, u8>(try_cast_slice_mut(data).unwrap()))
         });
 
-        if Clock::get()?.slot - state.valid_slot >= 50 {
-            return Err(error!(StrategyError::PythValidSlot))
-        }
-        if state.agg.status != PriceStatus::Trading {
-            return Err(error!(StrategyError::PythStatus))
-        }
-        if state.agg.price < 0 {
-            return Err(error!(StrategyError::PythNegativePrice))
-        }
-        if state.agg.conf.checked_mul(10).unwrap() > state.agg.price as u64 {
-            return Err(error!(StrategyError::PythConfidence));
+        let current_slot = Clock::get()?.slot;
+        let rejection = if current_slot - state.valid_slot >= 50 {
+            Some((OracleRejectionReason::StaleSlot, StrategyError::PythValidSlot))
+        } else if state.agg.status != PriceStatus::Trading {
+            Some((OracleRejectionReason::NotTrading, StrategyError::PythStatus))
+        } else if state.agg.price < 0 {
+            Some((OracleRejectionReason::NegativePrice, StrategyError::PythNegativePrice))
+        } else if state.agg.conf.checked_mul(10).unwrap() > state.agg.price as u64 {
+            Some((OracleRejectionReason::WideConfidence, StrategyError::PythConfidence))
+        } else {
+            None
+        };
+        if let Some((reason, error)) = rejection {
+            emit!(OracleRejected {
+                strategy: *strategy,
+                oracle: price_feed.key(),
+                reason,
+                price: state.agg.price,
+                confidence: state.agg.conf,
+                valid_slot: state.valid_slot,
+                current_slot,
+            });
+            return Err(error!(error));
         }
         let simple_price = SimplePrice {
             expo: state.expo,
             price: state.agg.price,
+            conf: state.agg.conf,
         };
         Ok(simple_price)
     }
 }
 
+/// Loads the oracle price multiplied by BIG_NUMBER
+pub fn load_scaled_price(price_feed: &AccountInfo, strategy: &Pubkey) -> Result<u128> {
+    Ok(load_scaled_price_and_confidence(price_feed, strategy)?.0)
+}
+
+/// Loads the oracle price and its confidence interval, both multiplied by BIG_NUMBER
+pub fn load_scaled_price_and_confidence(
+    price_feed: &AccountInfo,
+    strategy: &Pubkey,
+) -> Result<(u128, u128)> {
+    let oracle_price = Price::load(price_feed, strategy)?;
+    let scale = u64::pow(10, (-oracle_price.expo) as u32) as u128;
+    Ok((
+        BIG_NUMBER * oracle_price.price as u128 / scale,
+        BIG_NUMBER * oracle_price.conf as u128 / scale,
+    ))
+}
+
 #[cfg(target_endian = "little")]
 unsafe impl Zeroable for Price {}
 
@@ -0,0 +1,44 @@
+use crate::*;
+
+/// Strategies of one owner that a keeper updates together with update_portfolio
+#[account(zero_copy)]
+pub struct Portfolio {
+    pub owner: Pubkey,
+    /// Strategies in update order, only the first num_strategies are set
+    pub strategies: [Pubkey; MAX_PORTFOLIO_STRATEGIES],
+    pub num_strategies: u64,
+}
+
+impl Portfolio {
+    pub fn strategies(&self) -> &[Pubkey] {
+        &self.strategies[..self.num_strategies as usize]
+    }
+
+    pub fn add_strategy(&mut self, strategy: Pubkey) -> Result<()> {
+        require!(
+            !self.strategies().contains(&strategy),
+            StrategyError::PortfolioStrategyExists
+        );
+        require!(
+            (self.num_strategies as usize) < MAX_PORTFOLIO_STRATEGIES,
+            StrategyError::PortfolioFull
+        );
+        self.strategies[self.num_strategies as usize] = strategy;
+        self.num_strategies += 1;
+        Ok(())
+    }
+
+    /// Removes the strategy and keeps the order of the others
+    pub fn remove_strategy(&mut self, strategy: &Pubkey) -> Result<()> {
+        let num_strategies = self.num_strategies as usize;
+        let index = self
+            .strategies()
+            .iter()
+            .position(|key| key == strategy)
+            .ok_or(StrategyError::PortfolioStrategyNotFound)?;
+        self.strategies.copy_within(index + 1..num_strategies, index);
+        self.strategies[num_strategies - 1] = Pubkey::default();
+        self.num_strategies -= 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use crate::*;
+
+/// Pools LP capital into a program owned strategy, LP ownership is tracked by the share mint
+#[account(zero_copy)]
+pub struct StrategyVault {
+    pub phoenix_strategy: Pubkey,
+    pub share_mint: Pubkey,
+    /// Token accounts of the trader authority that hold the pooled funds outside the seat
+    pub quote_account: Pubkey,
+    pub base_account: Pubkey,
+    /// Shares burned by queued withdrawals that have not been paid out yet
+    pub queued_shares: u64,
+    /// If set to true, withdrawals can be queued while orders are resting
+    pub withdrawal_queue_enabled: bool,
+    padding: [u8; 7],
+    /// Wallet whose share token account receives the fees
+    pub fee_recipient: Pubkey,
+    /// Share of the NAV per share gain above the high-water mark taken as fee
+    pub performance_fee_in_bps: u64,
+    /// Yearly share of the NAV taken as fee
+    pub management_fee_in_bps: u64,
+    /// Highest NAV per share fees were charged at, scaled by BIG_NUMBER, 0 until the first accrual
+    pub high_water_mark: u64,
+    pub last_fee_unix_timestamp: i64,
+}
+
+impl StrategyVault {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        phoenix_strategy: Pubkey,
+        share_mint: Pubkey,
+        quote_account: Pubkey,
+        base_account: Pubkey,
+        withdrawal_queue_enabled: bool,
+        fee_recipient: Pubkey,
+        performance_fee_in_bps: u64,
+        management_fee_in_bps: u64,
+        unix_timestamp: i64,
+    ) -> Self {
+        StrategyVault {
+            phoenix_strategy,
+            share_mint,
+            quote_account,
+            base_account,
+            queued_shares: 0,
+            withdrawal_queue_enabled,
+            padding: [0; 7],
+            fee_recipient,
+            performance_fee_in_bps,
+            management_fee_in_bps,
+            high_water_mark: 0,
+            last_fee_unix_timestamp: unix_timestamp,
+        }
+    }
+
+    /// Shares that still have a claim on the vault, including burned but unpaid ones
+    pub fn get_total_shares(&self, share_supply: u64) -> u64 {
+        share_supply + self.queued_shares
+    }
+
+    /// Charges the management fee for the time since the last accrual and the performance fee
+    /// on the NAV per share gain above the high-water mark, returns the shares to mint to the
+    /// fee recipient so that it owns the fee value after dilution
+    pub fn accrue_fees(&mut self, nav: u64, total_shares: u64, unix_timestamp: i64) -> Result<u64> {
+        // Gaps longer than a year are charged as one year, which keeps the fee below the NAV
+        let elapsed = unix_timestamp
+            .saturating_sub(self.last_fee_unix_timestamp)
+            .clamp(0, SECONDS_PER_YEAR as i64) as u128;
+        self.last_fee_unix_timestamp = unix_timestamp;
+        // An emptied vault starts over with a new high-water mark
+        if total_shares == 0 || nav == 0 {
+            self.high_water_mark = 0;
+            return Ok(0);
+        }
+        let nav = nav as u128;
+        let total_shares = total_shares as u128;
+
+        let management_fee =
+            nav * self.management_fee_in_bps as u128 * elapsed / (10000 * SECONDS_PER_YEAR as u128);
+        let nav_per_share = get_nav_per_share(nav - management_fee, total_shares)?;
+        let mut performance_fee = 0;
+        if self.high_water_mark == 0 {
+            self.high_water_mark = nav_per_share;
+        } else if nav_per_share > self.high_water_mark {
+            performance_fee = (nav_per_share - self.high_water_mark) as u128 * total_shares
+                / BIG_NUMBER
+                * self.performance_fee_in_bps as u128
+                / 10000;
+        }
+
+        let fee = management_fee + performance_fee;
+        let fee_shares = u64::try_from(fee * total_shares / (nav - fee))
+            .map_err(|_| error!(StrategyError::InvalidVaultAmount))?;
+        self.high_water_mark = self
+            .high_water_mark
+            .max(get_nav_per_share(nav - fee, total_shares)?);
+        Ok(fee_shares)
+    }
+}
+
+/// NAV per share scaled by BIG_NUMBER, fails instead of truncating when it doesn't fit the
+/// high-water mark
+pub fn get_nav_per_share(nav: u128, total_shares: u128) -> Result<u64> {
+    u64::try_from(nav * BIG_NUMBER / total_shares)
+        .map_err(|_| error!(StrategyError::VaultNavPerShareOverflow))
+}
+
+/// Withdrawal that was queued while the strategy had orders resting
+#[account(zero_copy)]
+pub struct WithdrawalRequest {
+    pub vault: Pubkey,
+    pub lp: Pubkey,
+    pub shares: u64,
+    pub queued_slot: u64,
+}
+
+/// Values base atoms in quote atoms, prices are the scaled oracle prices
+pub fn get_value_in_quote_atoms(
+    quote_atoms: u64,
+    base_atoms: u64,
+    base_price: u128,
+    quote_price: u128,
+    header: &MarketHeader,
+) -> u64 {
+    let base_value = base_atoms as u128 * base_price
+        / u64::pow(10, header.base_params.decimals) as u128
+        * u64::pow(10, header.quote_params.decimals) as u128
+        / quote_price;
+    quote_atoms + base_value as u64
+}
+
+/// Shares and quote atoms the vault pretends to hold on top of its real ones, one whole quote
+/// token, a donation to the vault mostly accrues to them which makes inflating the share price
+/// unprofitable
+pub fn get_vault_virtual_offset(header: &MarketHeader) -> u64 {
+    u64::pow(10, header.quote_params.decimals)
+}
+
+/// Shares minted for a deposit, the first deposit mints one share per quote atom
+pub fn get_shares_for_deposit(
+    deposit_value: u64,
+    nav: u64,
+    total_shares: u64,
+    virtual_offset: u64,
+) -> Result<u64> {
+    let shares = deposit_value as u128 * (total_shares as u128 + virtual_offset as u128)
+        / (nav as u128 + virtual_offset as u128);
+    u64::try_from(shares).map_err(|_| error!(StrategyError::InvalidVaultAmount))
+}
+
+/// Quote atoms the shares are worth, priced the same way as deposits so an LP gets back what
+/// they deposited
+pub fn get_share_value(shares: u64, nav: u64, total_shares: u64, virtual_offset: u64) -> u64 {
+    let value = shares as u128 * (nav as u128 + virtual_offset as u128)
+        / (total_shares as u128 + virtual_offset as u128);
+    value.min(nav as u128) as u64
+}
+
+/// Part of a vault token balance paid out for shares worth share_value of the NAV
+pub fn get_pro_rata_amount(amount: u64, share_value: u64, nav: u64) -> u64 {
+    if nav == 0 {
+        return 0;
+    }
+    (amount as u128 * share_value as u128 / nav as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn new_vault(performance_fee_in_bps: u64, management_fee_in_bps: u64) -> StrategyVault {
+        StrategyVault::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            false,
+            Pubkey::default(),
+            performance_fee_in_bps,
+            management_fee_in_bps,
+            0,
+        )
+    }
+
+    #[test]
+    fn first_accrual_sets_high_water_mark() {
+        let mut vault = new_vault(2000, 0);
+        assert_eq!(vault.accrue_fees(1_000_000, 1_000_000, DAY).unwrap(), 0);
+        assert_eq!(vault.high_water_mark, BIG_NUMBER as u64);
+        assert_eq!(vault.last_fee_unix_timestamp, DAY);
+    }
+
+    #[test]
+    fn performance_fee_is_only_charged_above_high_water_mark() {
+        let mut vault = new_vault(2000, 0);
+        vault.accrue_fees(1_000_000, 1_000_000, 0).unwrap();
+
+        // 20% of the 200_000 gain, paid in shares worth 40_000 after dilution
+        let fee_shares = vault.accrue_fees(1_200_000, 1_000_000, DAY).unwrap();
+        assert_eq!(fee_shares, 34_482);
+        assert_eq!(vault.high_water_mark, 1_160_000_000_000);
+        let total_shares = 1_000_000 + fee_shares;
+
+        // The same NAV is not charged twice
+        assert_eq!(
+            vault.accrue_fees(1_200_000, total_shares, 2 * DAY).unwrap(),
+            0
+        );
+        let high_water_mark = vault.high_water_mark;
+
+        // A loss keeps the high-water mark and recovering it is free
+        assert_eq!(
+            vault.accrue_fees(900_000, total_shares, 3 * DAY).unwrap(),
+            0
+        );
+        assert_eq!(vault.high_water_mark, high_water_mark);
+        assert_eq!(
+            vault.accrue_fees(1_200_000, total_shares, 4 * DAY).unwrap(),
+            0
+        );
+        assert_eq!(vault.high_water_mark, high_water_mark);
+
+        // Only the gain above the high-water mark is charged
+        assert!(vault.accrue_fees(1_300_000, total_shares, 5 * DAY).unwrap() > 0);
+        assert!(vault.high_water_mark > high_water_mark);
+    }
+
+    #[test]
+    fn emptied_vault_resets_high_water_mark() {
+        let mut vault = new_vault(2000, 100);
+        vault.accrue_fees(1_000_000, 1_000_000, 0).unwrap();
+        vault.accrue_fees(2_000_000, 1_000_000, DAY).unwrap();
+        assert!(vault.high_water_mark > BIG_NUMBER as u64);
+
+        assert_eq!(vault.accrue_fees(0, 0, 2 * DAY).unwrap(), 0);
+        assert_eq!(vault.high_water_mark, 0);
+
+        // The next depositor starts over at their own NAV per share without paying a fee
+        assert_eq!(vault.accrue_fees(500_000, 1_000_000, 2 * DAY).unwrap(), 0);
+        assert_eq!(vault.high_water_mark, BIG_NUMBER as u64 / 2);
+    }
+
+    #[test]
+    fn management_fee_accrues_across_many_updates() {
+        let mut vault = new_vault(0, 100);
+        let nav = 1_000_000_000;
+        let mut total_shares = 1_000_000_000;
+        for day in 1..=365 {
+            total_shares += vault.accrue_fees(nav, total_shares, day * DAY).unwrap();
+        }
+        // Daily accruals compound to slightly less than the 1% yearly fee
+        let fee_share_in_bps = (total_shares - 1_000_000_000) * 10000 / total_shares;
+        assert_eq!(fee_share_in_bps, 99);
+        assert_eq!(vault.last_fee_unix_timestamp, 365 * DAY);
+    }
+
+    #[test]
+    fn management_fee_gap_is_charged_as_one_year() {
+        let mut vault = new_vault(0, 100);
+        let fee_shares = vault
+            .accrue_fees(1_000_000_000, 1_000_000_000, 3 * SECONDS_PER_YEAR as i64)
+            .unwrap();
+        // 1% of the NAV, paid in shares worth 10_000_000 after dilution
+        assert_eq!(fee_shares, 10_101_010);
+    }
+
+    #[test]
+    fn deposit_withdraw_round_trip() {
+        let virtual_offset = 1_000_000;
+        let deposit = 5_000_000;
+        let shares = get_shares_for_deposit(deposit, 0, 0, virtual_offset).unwrap();
+        assert_eq!(shares, deposit);
+        // A sole LP gets back their whole deposit
+        let value = get_share_value(shares, deposit, shares, virtual_offset);
+        assert_eq!(value, deposit);
+        assert_eq!(get_pro_rata_amount(deposit, value, deposit), deposit);
+
+        // A second LP after a 10% gain gets back about their deposit too
+        let nav = deposit * 11 / 10;
+        let second_shares = get_shares_for_deposit(1_000_000, nav, shares, virtual_offset).unwrap();
+        let nav = nav + 1_000_000;
+        let total_shares = shares + second_shares;
+        let value = get_share_value(second_shares, nav, total_shares, virtual_offset);
+        assert!((999_999..=1_000_000).contains(&value));
+        let first_value = get_share_value(shares, nav, total_shares, virtual_offset);
+        assert!(first_value + value <= nav);
+    }
+
+    #[test]
+    fn donation_does_not_inflate_share_price() {
+        let virtual_offset = 1_000_000;
+        // The attacker owns one share and donates to the vault before a victim deposits
+        let donation = 10_000_000;
+        let nav = 1 + donation;
+        let victim_shares = get_shares_for_deposit(donation, nav, 1, virtual_offset).unwrap();
+        assert!(victim_shares > 0);
+        let nav = nav + donation;
+        let total_shares = 1 + victim_shares;
+        let attacker_value = get_share_value(1, nav, total_shares, virtual_offset);
+        assert!(attacker_value < donation / 100);
+    }
+
+    #[test]
+    fn nav_per_share_overflow_fails() {
+        assert_eq!(
+            get_nav_per_share(1_000_000, 1).unwrap(),
+            1_000_000 * BIG_NUMBER as u64
+        );
+        assert_eq!(
+            get_nav_per_share(u64::MAX as u128, 1).unwrap_err(),
+            error!(StrategyError::VaultNavPerShareOverflow)
+        );
+    }
+}
@@ -3,6 +3,7 @@ import { Connection, Keypair, PublicKey, Transaction } from "@solana/web3.js";
 
 export const UBER_MM_PROGRAM_ID = new PublicKey("Exz7z8HpBjS7trD6ZbdWABdQyhK5ZvGkuV4UYoUiSTQQ");
 export const PHOENIX_PROGRAM_ID = new PublicKey("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+export const PHOENIX_SEAT_MANAGER_PROGRAM_ID = new PublicKey("PSMxQbAoDWDbvd9ezQJgARyq6R9L5kJAasaLDVcZwf1");
 
 export const tokenConfig: Map<string, TokenConfig> = new Map([
     [
@@ -20,6 +20,11 @@ export type UberMm = {
             "isMut": false,
             "isSigner": false
           },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
           {
             "name": "systemProgram",
             "isMut": false,
@@ -42,11 +47,21 @@ export type UberMm = {
           {
             "name": "postOnly",
             "type": "bool"
+          },
+          {
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
           }
         ]
       },
       {
-        "name": "updateQuotes",
+        "name": "initializeProgramOwned",
+        "docs": [
+          "Creates a strategy whose Phoenix trader is the program derived trader authority and",
+          "requests its seat, the market authority still has to approve the seat"
+        ],
         "accounts": [
           {
             "name": "phoenixStrategy",
@@ -55,9 +70,19 @@ export type UberMm = {
           },
           {
             "name": "user",
-            "isMut": false,
+            "isMut": true,
             "isSigner": true
           },
+          {
+            "name": "traderAuthority",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seatManagerProgram",
+            "isMut": false,
+            "isSigner": false
+          },
           {
             "name": "phoenixProgram",
             "isMut": false,
@@ -73,42 +98,121 @@ export type UberMm = {
             "isMut": true,
             "isSigner": false
           },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seatManager",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seatDepositCollector",
+            "isMut": true,
+            "isSigner": false
+          },
           {
             "name": "seat",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
             "isMut": false,
             "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteEdgeInBps",
+            "type": "u64"
           },
           {
-            "name": "quoteAccount",
+            "name": "quoteSizeInQuoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "priceImprovementBehavior",
+            "type": "u8"
+          },
+          {
+            "name": "postOnly",
+            "type": "bool"
+          },
+          {
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeWithSeat",
+        "docs": [
+          "Creates a strategy traded by the user's wallet and claims its seat through the",
+          "Phoenix seat manager in the same transaction"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
             "isMut": true,
             "isSigner": false
           },
           {
-            "name": "baseAccount",
+            "name": "user",
             "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "seatManagerProgram",
+            "isMut": false,
             "isSigner": false
           },
           {
-            "name": "quoteVault",
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
             "isMut": true,
             "isSigner": false
           },
           {
-            "name": "baseVault",
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seatManager",
             "isMut": true,
             "isSigner": false
           },
           {
-            "name": "tokenProgram",
+            "name": "seatDepositCollector",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
             "isMut": false,
             "isSigner": false
           }
         ],
         "args": [
-          {
-            "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
-            "type": "u64"
-          },
           {
             "name": "quoteEdgeInBps",
             "type": "u64"
@@ -126,278 +230,88 @@ export type UberMm = {
             "type": "bool"
           },
           {
-            "name": "useOracle",
-            "type": "bool"
-          },
-          {
-            "name": "margin",
-            "type": "u64"
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
           }
         ]
-      }
-    ],
-    "accounts": [
-      {
-        "name": "phoenixStrategyState",
-        "type": {
-          "kind": "struct",
-          "fields": [
-            {
-              "name": "trader",
-              "type": "publicKey"
-            },
-            {
-              "name": "market",
-              "type": "publicKey"
-            },
-            {
-              "name": "bidOrderSequenceNumber",
-              "type": "u64"
-            },
-            {
-              "name": "bidPriceInTicks",
-              "type": "u64"
-            },
-            {
-              "name": "initialBidSizeInBaseLots",
-              "type": "u64"
-            },
-            {
-              "name": "askOrderSequenceNumber",
-              "type": "u64"
-            },
-            {
-              "name": "askPriceInTicks",
-              "type": "u64"
-            },
-            {
-              "name": "initialAskSizeInBaseLots",
-              "type": "u64"
-            },
-            {
-              "name": "lastUpdateSlot",
-              "type": "u64"
-            },
-            {
-              "name": "lastUpdateUnixTimestamp",
-              "type": "i64"
-            },
-            {
-              "name": "quoteEdgeInBps",
-              "docs": [
-                "Number of basis points betweeen quoted price and fair price"
-              ],
-              "type": "u64"
-            },
-            {
-              "name": "quoteSizeInQuoteAtoms",
-              "docs": [
-                "Order notional size in quote atoms"
-              ],
-              "type": "u64"
-            },
-            {
-              "name": "postOnly",
-              "docs": [
-                "If set to true, the orders will never cross the spread"
-              ],
-              "type": "bool"
-            },
-            {
-              "name": "priceImprovementBehavior",
-              "docs": [
-                "Determines whether/how to improve BBO"
-              ],
-              "type": "u8"
-            },
-            {
-              "name": "padding",
-              "type": {
-                "array": [
-                  "u8",
-                  6
-                ]
-              }
-            }
-          ]
-        }
-      }
-    ],
-    "types": [
+      },
       {
-        "name": "OrderParams",
-        "type": {
-          "kind": "struct",
-          "fields": [
-            {
-              "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
-              "type": "u64"
-            },
-            {
-              "name": "strategyParams",
-              "type": {
-                "defined": "StrategyParams"
-              }
-            },
-            {
-              "name": "useOracle",
-              "type": "bool"
-            }
-          ]
-        }
+        "name": "migrateStrategy",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
       },
       {
-        "name": "StrategyParams",
-        "type": {
-          "kind": "struct",
-          "fields": [
-            {
-              "name": "quoteEdgeInBps",
-              "type": {
-                "option": "u64"
-              }
-            },
-            {
-              "name": "quoteSizeInQuoteAtoms",
-              "type": {
-                "option": "u64"
-              }
-            },
-            {
-              "name": "priceImprovementBehavior",
-              "type": {
-                "option": {
-                  "defined": "PriceImprovementBehavior"
-                }
-              }
-            },
-            {
-              "name": "postOnly",
-              "type": {
-                "option": "bool"
-              }
-            }
-          ]
-        }
-      },
-      {
-        "name": "PriceStatus",
-        "type": {
-          "kind": "enum",
-          "variants": [
-            {
-              "name": "Unknown"
-            },
-            {
-              "name": "Trading"
-            },
-            {
-              "name": "Halted"
-            },
-            {
-              "name": "Auction"
-            }
-          ]
-        }
-      },
-      {
-        "name": "CorpAction",
-        "type": {
-          "kind": "enum",
-          "variants": [
-            {
-              "name": "NoCorpAct"
-            }
-          ]
-        }
-      },
-      {
-        "name": "PriceType",
-        "type": {
-          "kind": "enum",
-          "variants": [
-            {
-              "name": "Unknown"
-            },
-            {
-              "name": "Price"
-            },
-            {
-              "name": "TWAP"
-            },
-            {
-              "name": "Volatility"
-            }
-          ]
-        }
-      },
-      {
-        "name": "PriceImprovementBehavior",
-        "type": {
-          "kind": "enum",
-          "variants": [
-            {
-              "name": "Ubermensch"
-            },
-            {
-              "name": "Join"
-            },
-            {
-              "name": "Dime"
-            },
-            {
-              "name": "Ignore"
-            }
-          ]
-        }
-      }
-    ],
-    "errors": [
-      {
-        "code": 6000,
-        "name": "NoReturnData"
-      },
-      {
-        "code": 6001,
-        "name": "InvalidStrategyParams"
-      },
-      {
-        "code": 6002,
-        "name": "EdgeMustBeNonZero"
-      },
-      {
-        "code": 6003,
-        "name": "InvalidPhoenixProgram"
-      },
-      {
-        "code": 6004,
-        "name": "FailedToDeserializePhoenixMarket"
-      },
-      {
-        "code": 6005,
-        "name": "PythStatus"
-      },
-      {
-        "code": 6006,
-        "name": "PythValidSlot"
-      },
-      {
-        "code": 6007,
-        "name": "PythNegativePrice"
+        "name": "pause",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy guardian"
+            ]
+          },
+          {
+            "name": "owner",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false,
+            "docs": [
+              "authority for the orders to be cancelled"
+            ]
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
       },
       {
-        "code": 6008,
-        "name": "PythConfidence"
-      }
-    ]
-  };
-  
-  export const UberMmIDL: UberMm = {
-    "version": "0.1.0",
-    "name": "uber_mm",
-    "instructions": [
-      {
-        "name": "initialize",
+        "name": "resume",
         "accounts": [
           {
             "name": "phoenixStrategy",
@@ -406,41 +320,93 @@ export type UberMm = {
           },
           {
             "name": "user",
-            "isMut": true,
+            "isMut": false,
             "isSigner": true
           },
           {
             "name": "market",
             "isMut": false,
             "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "cancelIfStale",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
           },
           {
-            "name": "systemProgram",
+            "name": "phoenixProgram",
             "isMut": false,
             "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
           }
         ],
-        "args": [
+        "args": []
+      },
+      {
+        "name": "cancelAllQuotes",
+        "accounts": [
           {
-            "name": "quoteEdgeInBps",
-            "type": "u64"
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
           },
           {
-            "name": "quoteSizeInQuoteAtoms",
-            "type": "u64"
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy operator"
+            ]
           },
           {
-            "name": "priceImprovementBehavior",
-            "type": "u8"
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
           },
           {
-            "name": "postOnly",
-            "type": "bool"
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
           }
-        ]
+        ],
+        "args": []
       },
       {
-        "name": "updateQuotes",
+        "name": "depositToSeat",
+        "docs": [
+          "Moves funds from the trader's token accounts into the Phoenix seat,",
+          "None deposits the whole token account balance"
+        ],
         "accounts": [
           {
             "name": "phoenixStrategy",
@@ -452,6 +418,11 @@ export type UberMm = {
             "isMut": false,
             "isSigner": true
           },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
           {
             "name": "phoenixProgram",
             "isMut": false,
@@ -500,180 +471,5374 @@ export type UberMm = {
         ],
         "args": [
           {
-            "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
-            "type": "u64"
+            "name": "quoteLots",
+            "type": {
+              "option": "u64"
+            }
           },
           {
-            "name": "quoteEdgeInBps",
-            "type": "u64"
-          },
+            "name": "baseLots",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "withdrawFromSeat",
+        "docs": [
+          "Moves free funds from the Phoenix seat to the trader's token accounts,",
+          "None withdraws everything that is not locked in orders"
+        ],
+        "accounts": [
           {
-            "name": "quoteSizeInQuoteAtoms",
-            "type": "u64"
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
           },
           {
-            "name": "priceImprovementBehavior",
-            "type": "u8"
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
           },
           {
-            "name": "postOnly",
-            "type": "bool"
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
           },
           {
-            "name": "useOracle",
-            "type": "bool"
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
           },
           {
-            "name": "margin",
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteLots",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "baseLots",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "closeStrategy",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "vault",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "history",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "withdrawTraderFunds",
+        "docs": [
+          "Moves tokens out of a token account owned by the trader authority to the owner,",
+          "None transfers the whole balance"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false,
+            "docs": [
+              "strategy is closed"
+            ]
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "vault",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "sourceAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "destinationAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "amount",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeVault",
+        "docs": [
+          "Creates the LP vault of a program owned strategy, along with its share mint and the",
+          "trader authority token accounts that hold the pooled funds"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteMint",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseMint",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "rent",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "withdrawalQueueEnabled",
+            "type": "bool"
+          },
+          {
+            "name": "feeRecipient",
+            "type": "publicKey"
+          },
+          {
+            "name": "performanceFeeInBps",
+            "type": "u64"
+          },
+          {
+            "name": "managementFeeInBps",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "crystallizeFees",
+        "docs": [
+          "Mints the management and performance fees accrued since the last crystallization",
+          "to the fee recipient, anyone can crank it"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "feeRecipientAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "depositToVault",
+        "docs": [
+          "Deposits into the vault and mints shares priced at the vault NAV,",
+          "pending fees are crystallized first so the depositor doesn't pay for them"
+        ],
+        "accounts": [
+          {
+            "name": "fees",
+            "accounts": [
+              {
+                "name": "vault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "phoenixStrategy",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "traderAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "market",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "shareMint",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "feeRecipientAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "quoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "marketConfig",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "baseOracle",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteOracle",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "tokenProgram",
+                "isMut": false,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "lp",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "lpQuoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lpBaseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "baseAtoms",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "withdrawFromVault",
+        "docs": [
+          "Burns shares and pays out their share of the vault funds, pending fees are",
+          "crystallized first so the LP pays them too, fails while the strategy has orders resting"
+        ],
+        "accounts": [
+          {
+            "name": "payout",
+            "accounts": [
+              {
+                "name": "fees",
+                "accounts": [
+                  {
+                    "name": "vault",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "phoenixStrategy",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "traderAuthority",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "market",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "shareMint",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "feeRecipientAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "marketConfig",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "tokenProgram",
+                    "isMut": false,
+                    "isSigner": false
+                  }
+                ]
+              },
+              {
+                "name": "phoenixProgram",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "logAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpQuoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpBaseAccount",
+                "isMut": true,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "lp",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "shares",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "queueWithdrawal",
+        "docs": [
+          "Burns shares now and records a withdrawal that is paid out by process_withdrawal",
+          "once the strategy has no orders resting"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "withdrawalRequest",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lp",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "shares",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "processWithdrawal",
+        "docs": [
+          "Pays out a queued withdrawal, anyone can crank it once no orders are resting"
+        ],
+        "accounts": [
+          {
+            "name": "payout",
+            "accounts": [
+              {
+                "name": "fees",
+                "accounts": [
+                  {
+                    "name": "vault",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "phoenixStrategy",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "traderAuthority",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "market",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "shareMint",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "feeRecipientAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "marketConfig",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "tokenProgram",
+                    "isMut": false,
+                    "isSigner": false
+                  }
+                ]
+              },
+              {
+                "name": "phoenixProgram",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "logAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpQuoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpBaseAccount",
+                "isMut": true,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "withdrawalRequest",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lp",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "setStrategyParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "StrategyParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setOperator",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "operator",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setRiskParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "RiskParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setExecutionParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "ExecutionParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "updateQuotes",
+        "docs": [
+          "Remaining accounts are the registered base and quote oracles of the market, optionally",
+          "followed by the strategy history account",
+          "Only the owner can override the fair price"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy operator"
+            ]
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeHistory",
+        "docs": [
+          "Creates the optional history account of a strategy, update_quotes appends a record to",
+          "it when it is passed after the oracles"
+        ],
+        "accounts": [
+          {
+            "name": "history",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "closeHistory",
+        "docs": [
+          "Has to run before close_strategy, the owner is checked against the strategy"
+        ],
+        "accounts": [
+          {
+            "name": "history",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "getPnl",
+        "docs": [
+          "Returns the strategy PnL through the return data, unrealized PnL is as of the last",
+          "update_quotes that used the oracle"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [],
+        "returns": {
+          "defined": "StrategyPnl"
+        }
+      },
+      {
+        "name": "initializeConfig",
+        "docs": [
+          "Creates the global config, only the program upgrade authority can become the first admin"
+        ],
+        "accounts": [
+          {
+            "name": "config",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "program",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "programData",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "setConfigAdmin",
+        "accounts": [
+          {
+            "name": "config",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": false,
+            "isSigner": true
+          }
+        ],
+        "args": [
+          {
+            "name": "admin",
+            "type": "publicKey"
+          }
+        ]
+      },
+      {
+        "name": "registerMarket",
+        "docs": [
+          "Adds a Phoenix market to the registry, strategies can only be created for",
+          "registered markets"
+        ],
+        "accounts": [
+          {
+            "name": "marketConfig",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "config",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "oracleType",
+            "type": {
+              "defined": "OracleType"
+            }
+          }
+        ]
+      },
+      {
+        "name": "updateMarketConfig",
+        "docs": [
+          "Replaces the oracles of a registered market and enables or disables it, quotes",
+          "can't be updated on a disabled market"
+        ],
+        "accounts": [
+          {
+            "name": "marketConfig",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "config",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "oracleType",
+            "type": {
+              "defined": "OracleType"
+            }
+          },
+          {
+            "name": "enabled",
+            "type": "bool"
+          }
+        ]
+      },
+      {
+        "name": "initializePortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "addToPortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "removeFromPortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          }
+        ],
+        "args": [
+          {
+            "name": "strategy",
+            "type": "publicKey"
+          }
+        ]
+      },
+      {
+        "name": "updatePortfolio",
+        "docs": [
+          "Updates the quotes of every strategy in the portfolio in one transaction. Remaining",
+          "accounts hold one group per strategy in portfolio order, each group being the",
+          "update_quotes accounts followed by the registered base and quote oracles and optionally",
+          "the strategy history account. Overrides are either empty or one per strategy.",
+          "Strategies that can't quote right now, e.g. paused, rate limited or with a rejected",
+          "oracle, are logged and skipped. Closed strategies are removed from the portfolio by",
+          "close_strategy, but a failing Phoenix CPI or a group with invalid accounts still fails",
+          "the whole transaction"
+        ],
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "fairPriceOverrides",
+            "type": {
+              "vec": {
+                "option": "u64"
+              }
+            }
+          }
+        ]
+      }
+    ],
+    "accounts": [
+      {
+        "name": "globalConfig",
+        "docs": [
+          "Program wide settings, the admin manages the market registry"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "admin",
+              "type": "publicKey"
+            }
+          ]
+        }
+      },
+      {
+        "name": "marketConfig",
+        "docs": [
+          "Registry entry of a Phoenix market, strategies can only be created and quoted on",
+          "enabled markets and always use the registered oracles"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "market",
+              "type": "publicKey"
+            },
+            {
+              "name": "baseOracle",
+              "type": "publicKey"
+            },
+            {
+              "name": "quoteOracle",
+              "type": "publicKey"
+            },
+            {
+              "name": "oracleType",
+              "type": "u8"
+            },
+            {
+              "name": "enabled",
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  6
+                ]
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "strategyHistory",
+        "docs": [
+          "Ring buffer with the last HISTORY_LENGTH updates of a strategy"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "phoenixStrategy",
+              "type": "publicKey"
+            },
+            {
+              "name": "nextIndex",
+              "docs": [
+                "Index the next record is written at"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "numRecords",
+              "type": "u64"
+            },
+            {
+              "name": "records",
+              "type": {
+                "array": [
+                  {
+                    "defined": "UpdateRecord"
+                  },
+                  64
+                ]
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "portfolio",
+        "docs": [
+          "Strategies of one owner that a keeper updates together with update_portfolio"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "owner",
+              "type": "publicKey"
+            },
+            {
+              "name": "strategies",
+              "docs": [
+                "Strategies in update order, only the first num_strategies are set"
+              ],
+              "type": {
+                "array": [
+                  "publicKey",
+                  8
+                ]
+              }
+            },
+            {
+              "name": "numStrategies",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "strategyVault",
+        "docs": [
+          "Pools LP capital into a program owned strategy, LP ownership is tracked by the share mint"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "phoenixStrategy",
+              "type": "publicKey"
+            },
+            {
+              "name": "shareMint",
+              "type": "publicKey"
+            },
+            {
+              "name": "quoteAccount",
+              "docs": [
+                "Token accounts of the trader authority that hold the pooled funds outside the seat"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "baseAccount",
+              "type": "publicKey"
+            },
+            {
+              "name": "queuedShares",
+              "docs": [
+                "Shares burned by queued withdrawals that have not been paid out yet"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "withdrawalQueueEnabled",
+              "docs": [
+                "If set to true, withdrawals can be queued while orders are resting"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  7
+                ]
+              }
+            },
+            {
+              "name": "feeRecipient",
+              "docs": [
+                "Wallet whose share token account receives the fees"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "performanceFeeInBps",
+              "docs": [
+                "Share of the NAV per share gain above the high-water mark taken as fee"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "managementFeeInBps",
+              "docs": [
+                "Yearly share of the NAV taken as fee"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "highWaterMark",
+              "docs": [
+                "Highest NAV per share fees were charged at, scaled by BIG_NUMBER, 0 until the first accrual"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "lastFeeUnixTimestamp",
+              "type": "i64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "withdrawalRequest",
+        "docs": [
+          "Withdrawal that was queued while the strategy had orders resting"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "vault",
+              "type": "publicKey"
+            },
+            {
+              "name": "lp",
+              "type": "publicKey"
+            },
+            {
+              "name": "shares",
+              "type": "u64"
+            },
+            {
+              "name": "queuedSlot",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "phoenixStrategyState",
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "trader",
+              "type": "publicKey"
+            },
+            {
+              "name": "market",
+              "type": "publicKey"
+            },
+            {
+              "name": "bidOrderSequenceNumber",
+              "type": "u64"
+            },
+            {
+              "name": "bidPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "initialBidSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "askOrderSequenceNumber",
+              "type": "u64"
+            },
+            {
+              "name": "askPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "initialAskSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "lastUpdateSlot",
+              "type": "u64"
+            },
+            {
+              "name": "lastUpdateUnixTimestamp",
+              "type": "i64"
+            },
+            {
+              "name": "quoteEdgeInBps",
+              "docs": [
+                "Number of basis points betweeen quoted price and fair price"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "quoteSizeInQuoteAtoms",
+              "docs": [
+                "Order notional size in quote atoms"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "postOnly",
+              "docs": [
+                "If set to true, the orders will never cross the spread, otherwise they are sent as limit",
+                "orders that can take and use the self-trade behavior"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "priceImprovementBehavior",
+              "docs": [
+                "Determines whether/how to improve BBO"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "paused",
+              "docs": [
+                "If set to true, update_quotes refuses to place orders"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "version",
+              "docs": [
+                "Layout version of the account, 0 for accounts created before versioning"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "hasVault",
+              "docs": [
+                "Set once a vault pools LP capital into the strategy, the fair price can't be overridden"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  3
+                ]
+              }
+            },
+            {
+              "name": "guardian",
+              "docs": [
+                "Optional key that is allowed to pause the strategy, Pubkey::default() if unset"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "maxBookDeviationInBps",
+              "docs": [
+                "Maximum distance between the best bid/ask of other traders and the oracle fair price",
+                "before quotes are pulled, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "minSlotsBetweenUpdates",
+              "docs": [
+                "Minimum number of slots between two update_quotes calls, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxOrderNotionalInQuoteAtoms",
+              "docs": [
+                "Maximum notional of a single order in quote atoms, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxCancelReplaceCycles",
+              "docs": [
+                "Maximum number of cancel-replace cycles placing orders per window, cancels alone are",
+                "never limited, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceWindowInSlots",
+              "docs": [
+                "Length of the cancel-replace window in slots"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceWindowStartSlot",
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceCyclesInWindow",
+              "type": "u64"
+            },
+            {
+              "name": "minQuotePriceInTicks",
+              "docs": [
+                "Lowest price in ticks we are allowed to quote at, 0 disables the bound"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxQuotePriceInTicks",
+              "docs": [
+                "Highest price in ticks we are allowed to quote at, 0 disables the bound"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "heartbeatInSlots",
+              "docs": [
+                "Number of slots without an update after which anyone can cancel our quotes, 0 disables"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "selfTradeBehavior",
+              "docs": [
+                "What happens when a limit order would match against one of our own orders"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "failSilentlyOnInsufficientFunds",
+              "docs": [
+                "If set to true, a side without enough funds is skipped instead of failing the transaction"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "executionPadding",
+              "type": {
+                "array": [
+                  "u8",
+                  6
+                ]
+              }
+            },
+            {
+              "name": "quoteReserveInQuoteAtoms",
+              "docs": [
+                "Quote atoms that are never committed to orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "baseReserveInBaseAtoms",
+              "docs": [
+                "Base atoms that are never committed to orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "minOrderSizeInBaseLots",
+              "docs": [
+                "Sides that can't be funded for at least this size are skipped"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "marginInTicks",
+              "docs": [
+                "Minimum distance in ticks between the fair price and the BBO for Ubermensch mode to keep",
+                "quoting at the BBO"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "operator",
+              "docs": [
+                "Optional key that can update and cancel quotes but not change parameters, move funds or",
+                "override the oracle fair price, Pubkey::default() if unset"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "owner",
+              "docs": [
+                "Wallet that created the strategy, differs from the trader when the program holds the seat"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "seat",
+              "docs": [
+                "Phoenix seat of the trader on the market"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "numBidFills",
+              "type": "u64"
+            },
+            {
+              "name": "numAskFills",
+              "type": "u64"
+            },
+            {
+              "name": "baseLotsBought",
+              "type": "u64"
+            },
+            {
+              "name": "baseLotsSold",
+              "type": "u64"
+            },
+            {
+              "name": "quoteAtomsPaid",
+              "type": "u64"
+            },
+            {
+              "name": "quoteAtomsReceived",
+              "type": "u64"
+            },
+            {
+              "name": "averageBuyPriceInTicks",
+              "docs": [
+                "Volume weighted average price of our filled bids"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "averageSellPriceInTicks",
+              "docs": [
+                "Volume weighted average price of our filled asks"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "numCancelReplaceCycles",
+              "docs": [
+                "Total number of updates that cancelled or placed orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "quoteLotsFreeBaseline",
+              "docs": [
+                "Free lots of the trader right after the tracked orders were placed, moved by seat",
+                "deposits and withdrawals and by the fills booked since"
+              ],
+              "type": "i64"
+            },
+            {
+              "name": "baseLotsFreeBaseline",
+              "type": "i64"
+            },
+            {
+              "name": "netPositionInBaseLots",
+              "docs": [
+                "Positive when long, negative when short"
+              ],
+              "type": "i64"
+            },
+            {
+              "name": "averageEntryPriceInTicks",
+              "docs": [
+                "Average price the open position was entered at after fees, 0 when flat"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "realizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "unrealizedPnlInQuoteAtoms",
+              "docs": [
+                "Open position marked against the oracle fair price of the last update"
+              ],
+              "type": "i64"
+            },
+            {
+              "name": "lastMarkPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "positionCostInQuoteAtoms",
+              "docs": [
+                "Quote atoms paid for the open position including fees, negative for the quote atoms",
+                "received when short"
+              ],
+              "type": "i64"
+            }
+          ]
+        }
+      }
+    ],
+    "types": [
+      {
+        "name": "UpdateRecord",
+        "docs": [
+          "What update_quotes saw and did in one call"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "slot",
+              "type": "u64"
+            },
+            {
+              "name": "fairPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "oracleConfidenceInTicks",
+              "docs": [
+                "Confidence interval of the base oracle price, 0 when the fair price was overridden"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "bestBidInTicks",
+              "docs": [
+                "Best prices of other traders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "bestAskInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "bidPriceInTicks",
+              "docs": [
+                "Our resting orders after the update, 0 if there is none"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "bidSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "askPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "askSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "quoteAtomsHeld",
+              "docs": [
+                "Trader inventory in the token accounts and the Phoenix seat"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "baseAtomsHeld",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "StrategyPnl",
+        "docs": [
+          "PnL of a strategy as returned by get_pnl"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "realizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "unrealizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "netPositionInBaseLots",
+              "type": "i64"
+            },
+            {
+              "name": "averageEntryPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "lastMarkPriceInTicks",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "StrategyParams",
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "quoteEdgeInBps",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "quoteSizeInQuoteAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "priceImprovementBehavior",
+              "type": {
+                "option": {
+                  "defined": "PriceImprovementBehavior"
+                }
+              }
+            },
+            {
+              "name": "postOnly",
+              "type": {
+                "option": "bool"
+              }
+            },
+            {
+              "name": "marginInTicks",
+              "type": {
+                "option": "u64"
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "RiskParams",
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "maxBookDeviationInBps",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "minSlotsBetweenUpdates",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "maxOrderNotionalInQuoteAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "maxCancelReplaceCycles",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "cancelReplaceWindowInSlots",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "minQuotePriceInTicks",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "maxQuotePriceInTicks",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "heartbeatInSlots",
+              "type": {
+                "option": "u64"
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "ExecutionParams",
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "selfTradeBehavior",
+              "type": {
+                "option": {
+                  "defined": "SelfTradeBehavior"
+                }
+              }
+            },
+            {
+              "name": "failSilentlyOnInsufficientFunds",
+              "type": {
+                "option": "bool"
+              }
+            },
+            {
+              "name": "quoteReserveInQuoteAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "baseReserveInBaseAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "minOrderSizeInBaseLots",
+              "type": {
+                "option": "u64"
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "OracleType",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Pyth"
+            }
+          ]
+        }
+      },
+      {
+        "name": "QuoteSide",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Bid"
+            },
+            {
+              "name": "Ask"
+            }
+          ]
+        }
+      },
+      {
+        "name": "OracleRejectionReason",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "StaleSlot"
+            },
+            {
+              "name": "NotTrading"
+            },
+            {
+              "name": "NegativePrice"
+            },
+            {
+              "name": "WideConfidence"
+            }
+          ]
+        }
+      },
+      {
+        "name": "PriceStatus",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Unknown"
+            },
+            {
+              "name": "Trading"
+            },
+            {
+              "name": "Halted"
+            },
+            {
+              "name": "Auction"
+            }
+          ]
+        }
+      },
+      {
+        "name": "CorpAction",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "NoCorpAct"
+            }
+          ]
+        }
+      },
+      {
+        "name": "PriceType",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Unknown"
+            },
+            {
+              "name": "Price"
+            },
+            {
+              "name": "TWAP"
+            },
+            {
+              "name": "Volatility"
+            }
+          ]
+        }
+      },
+      {
+        "name": "PriceImprovementBehavior",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Ubermensch"
+            },
+            {
+              "name": "Join"
+            },
+            {
+              "name": "Dime"
+            },
+            {
+              "name": "Ignore"
+            }
+          ]
+        }
+      },
+      {
+        "name": "SelfTradeBehavior",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Abort"
+            },
+            {
+              "name": "CancelProvide"
+            },
+            {
+              "name": "DecrementTake"
+            }
+          ]
+        }
+      }
+    ],
+    "events": [
+      {
+        "name": "StrategyInitialized",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "owner",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "trader",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "market",
+            "type": "publicKey",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "StrategyParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "StrategyParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "RiskParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "RiskParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "ExecutionParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "ExecutionParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OperatorChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "operator",
+            "type": {
+              "option": "publicKey"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "QuotePlaced",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "side",
+            "type": {
+              "defined": "QuoteSide"
+            },
+            "index": false
+          },
+          {
+            "name": "priceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "sizeInBaseLots",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "orderSequenceNumber",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OrdersCancelled",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "orderSequenceNumbers",
+            "type": {
+              "vec": "u64"
+            },
+            "index": false
+          },
+          {
+            "name": "cancelledAll",
+            "type": "bool",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "FillDetected",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "side",
+            "type": {
+              "defined": "QuoteSide"
+            },
+            "index": false
+          },
+          {
+            "name": "priceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "baseLots",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "quoteAtoms",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OracleRejected",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "oracle",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "reason",
+            "type": {
+              "defined": "OracleRejectionReason"
+            },
+            "index": false
+          },
+          {
+            "name": "price",
+            "type": "i64",
+            "index": false
+          },
+          {
+            "name": "confidence",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "validSlot",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "currentSlot",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "CircuitBreakerTripped",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "bestBidInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "bestAskInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "fairPriceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "deviationInBps",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "maxBookDeviationInBps",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      }
+    ],
+    "errors": [
+      {
+        "code": 6000,
+        "name": "NoReturnData"
+      },
+      {
+        "code": 6001,
+        "name": "InvalidStrategyParams"
+      },
+      {
+        "code": 6002,
+        "name": "EdgeMustBeNonZero"
+      },
+      {
+        "code": 6003,
+        "name": "InvalidPhoenixProgram"
+      },
+      {
+        "code": 6004,
+        "name": "FailedToDeserializePhoenixMarket"
+      },
+      {
+        "code": 6005,
+        "name": "PythStatus"
+      },
+      {
+        "code": 6006,
+        "name": "PythValidSlot"
+      },
+      {
+        "code": 6007,
+        "name": "PythNegativePrice"
+      },
+      {
+        "code": 6008,
+        "name": "PythConfidence"
+      },
+      {
+        "code": 6009,
+        "name": "StrategyPaused"
+      },
+      {
+        "code": 6010,
+        "name": "InvalidPauseAuthority"
+      },
+      {
+        "code": 6011,
+        "name": "UpdateTooFrequent"
+      },
+      {
+        "code": 6012,
+        "name": "OrderNotionalTooLarge"
+      },
+      {
+        "code": 6013,
+        "name": "TooManyCancelReplaceCycles"
+      },
+      {
+        "code": 6014,
+        "name": "TraderNotProgramOwned"
+      },
+      {
+        "code": 6015,
+        "name": "StrategyNotStale"
+      },
+      {
+        "code": 6016,
+        "name": "InvalidStrategyAuthority"
+      },
+      {
+        "code": 6017,
+        "name": "InvalidStrategyAccount"
+      },
+      {
+        "code": 6018,
+        "name": "StrategyNeedsMigration"
+      },
+      {
+        "code": 6019,
+        "name": "InvalidVaultMint"
+      },
+      {
+        "code": 6020,
+        "name": "InvalidVaultAmount"
+      },
+      {
+        "code": 6021,
+        "name": "InvalidVaultTokenAccount"
+      },
+      {
+        "code": 6022,
+        "name": "VaultOrdersResting"
+      },
+      {
+        "code": 6023,
+        "name": "WithdrawalQueueDisabled"
+      },
+      {
+        "code": 6024,
+        "name": "StrategyHasVault"
+      },
+      {
+        "code": 6025,
+        "name": "InvalidVaultFees"
+      },
+      {
+        "code": 6026,
+        "name": "PortfolioFull"
+      },
+      {
+        "code": 6027,
+        "name": "PortfolioStrategyExists"
+      },
+      {
+        "code": 6028,
+        "name": "PortfolioStrategyNotFound"
+      },
+      {
+        "code": 6029,
+        "name": "InvalidPortfolioAccounts"
+      },
+      {
+        "code": 6030,
+        "name": "InvalidConfigAdmin"
+      },
+      {
+        "code": 6031,
+        "name": "MarketDisabled"
+      },
+      {
+        "code": 6032,
+        "name": "InvalidOracleAccount"
+      },
+      {
+        "code": 6033,
+        "name": "InvalidPriceImprovementBehavior"
+      },
+      {
+        "code": 6034,
+        "name": "InvalidSelfTradeBehavior"
+      },
+      {
+        "code": 6035,
+        "name": "InvalidOracleType"
+      },
+      {
+        "code": 6036,
+        "name": "InvalidHistoryAccount"
+      },
+      {
+        "code": 6037,
+        "name": "FairPriceOverrideNotAllowed"
+      },
+      {
+        "code": 6038,
+        "name": "VaultNavPerShareOverflow"
+      },
+      {
+        "code": 6039,
+        "name": "StrategyHasHistory"
+      },
+      {
+        "code": 6040,
+        "name": "UnsupportedStrategyVersion"
+      }
+    ]
+  };
+  
+  export const UberMmIDL: UberMm = {
+    "version": "0.1.0",
+    "name": "uber_mm",
+    "instructions": [
+      {
+        "name": "initialize",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteEdgeInBps",
+            "type": "u64"
+          },
+          {
+            "name": "quoteSizeInQuoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "priceImprovementBehavior",
+            "type": "u8"
+          },
+          {
+            "name": "postOnly",
+            "type": "bool"
+          },
+          {
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeProgramOwned",
+        "docs": [
+          "Creates a strategy whose Phoenix trader is the program derived trader authority and",
+          "requests its seat, the market authority still has to approve the seat"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seatManagerProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seatManager",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seatDepositCollector",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteEdgeInBps",
+            "type": "u64"
+          },
+          {
+            "name": "quoteSizeInQuoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "priceImprovementBehavior",
+            "type": "u8"
+          },
+          {
+            "name": "postOnly",
+            "type": "bool"
+          },
+          {
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeWithSeat",
+        "docs": [
+          "Creates a strategy traded by the user's wallet and claims its seat through the",
+          "Phoenix seat manager in the same transaction"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "seatManagerProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seatManager",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seatDepositCollector",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteEdgeInBps",
+            "type": "u64"
+          },
+          {
+            "name": "quoteSizeInQuoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "priceImprovementBehavior",
+            "type": "u8"
+          },
+          {
+            "name": "postOnly",
+            "type": "bool"
+          },
+          {
+            "name": "guardian",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "migrateStrategy",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "pause",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy guardian"
+            ]
+          },
+          {
+            "name": "owner",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false,
+            "docs": [
+              "authority for the orders to be cancelled"
+            ]
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "resume",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "cancelIfStale",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "cancelAllQuotes",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy operator"
+            ]
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "depositToSeat",
+        "docs": [
+          "Moves funds from the trader's token accounts into the Phoenix seat,",
+          "None deposits the whole token account balance"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteLots",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "baseLots",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "withdrawFromSeat",
+        "docs": [
+          "Moves free funds from the Phoenix seat to the trader's token accounts,",
+          "None withdraws everything that is not locked in orders"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteLots",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "baseLots",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "closeStrategy",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "vault",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "history",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "withdrawTraderFunds",
+        "docs": [
+          "Moves tokens out of a token account owned by the trader authority to the owner,",
+          "None transfers the whole balance"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false,
+            "docs": [
+              "strategy is closed"
+            ]
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "vault",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "sourceAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "destinationAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "amount",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeVault",
+        "docs": [
+          "Creates the LP vault of a program owned strategy, along with its share mint and the",
+          "trader authority token accounts that hold the pooled funds"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteMint",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseMint",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "rent",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "withdrawalQueueEnabled",
+            "type": "bool"
+          },
+          {
+            "name": "feeRecipient",
+            "type": "publicKey"
+          },
+          {
+            "name": "performanceFeeInBps",
+            "type": "u64"
+          },
+          {
+            "name": "managementFeeInBps",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "crystallizeFees",
+        "docs": [
+          "Mints the management and performance fees accrued since the last crystallization",
+          "to the fee recipient, anyone can crank it"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "traderAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "feeRecipientAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "depositToVault",
+        "docs": [
+          "Deposits into the vault and mints shares priced at the vault NAV,",
+          "pending fees are crystallized first so the depositor doesn't pay for them"
+        ],
+        "accounts": [
+          {
+            "name": "fees",
+            "accounts": [
+              {
+                "name": "vault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "phoenixStrategy",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "traderAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "market",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "shareMint",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "feeRecipientAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "quoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "marketConfig",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "baseOracle",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteOracle",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "tokenProgram",
+                "isMut": false,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "lp",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "lpQuoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lpBaseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "quoteAtoms",
+            "type": "u64"
+          },
+          {
+            "name": "baseAtoms",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "withdrawFromVault",
+        "docs": [
+          "Burns shares and pays out their share of the vault funds, pending fees are",
+          "crystallized first so the LP pays them too, fails while the strategy has orders resting"
+        ],
+        "accounts": [
+          {
+            "name": "payout",
+            "accounts": [
+              {
+                "name": "fees",
+                "accounts": [
+                  {
+                    "name": "vault",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "phoenixStrategy",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "traderAuthority",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "market",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "shareMint",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "feeRecipientAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "marketConfig",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "tokenProgram",
+                    "isMut": false,
+                    "isSigner": false
+                  }
+                ]
+              },
+              {
+                "name": "phoenixProgram",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "logAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpQuoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpBaseAccount",
+                "isMut": true,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "lp",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "shares",
+            "type": "u64"
+          }
+        ]
+      },
+      {
+        "name": "queueWithdrawal",
+        "docs": [
+          "Burns shares now and records a withdrawal that is paid out by process_withdrawal",
+          "once the strategy has no orders resting"
+        ],
+        "accounts": [
+          {
+            "name": "vault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "withdrawalRequest",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "shareMint",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lp",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "lpShareAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "shares",
             "type": "u64"
           }
         ]
+      },
+      {
+        "name": "processWithdrawal",
+        "docs": [
+          "Pays out a queued withdrawal, anyone can crank it once no orders are resting"
+        ],
+        "accounts": [
+          {
+            "name": "payout",
+            "accounts": [
+              {
+                "name": "fees",
+                "accounts": [
+                  {
+                    "name": "vault",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "phoenixStrategy",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "traderAuthority",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "market",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "shareMint",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "feeRecipientAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseAccount",
+                    "isMut": true,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "marketConfig",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "baseOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "quoteOracle",
+                    "isMut": false,
+                    "isSigner": false
+                  },
+                  {
+                    "name": "tokenProgram",
+                    "isMut": false,
+                    "isSigner": false
+                  }
+                ]
+              },
+              {
+                "name": "phoenixProgram",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "logAuthority",
+                "isMut": false,
+                "isSigner": false
+              },
+              {
+                "name": "quoteVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "baseVault",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpQuoteAccount",
+                "isMut": true,
+                "isSigner": false
+              },
+              {
+                "name": "lpBaseAccount",
+                "isMut": true,
+                "isSigner": false
+              }
+            ]
+          },
+          {
+            "name": "withdrawalRequest",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "lp",
+            "isMut": true,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "setStrategyParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "StrategyParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setOperator",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "operator",
+            "type": {
+              "option": "publicKey"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setRiskParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "RiskParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "setExecutionParams",
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "params",
+            "type": {
+              "defined": "ExecutionParams"
+            }
+          }
+        ]
+      },
+      {
+        "name": "updateQuotes",
+        "docs": [
+          "Remaining accounts are the registered base and quote oracles of the market, optionally",
+          "followed by the strategy history account",
+          "Only the owner can override the fair price"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "authority",
+            "isMut": false,
+            "isSigner": true,
+            "docs": [
+              "Either the owner or the strategy operator"
+            ]
+          },
+          {
+            "name": "trader",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixProgram",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "logAuthority",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "market",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "marketConfig",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "seat",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseAccount",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "quoteVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "baseVault",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "tokenProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      },
+      {
+        "name": "initializeHistory",
+        "docs": [
+          "Creates the optional history account of a strategy, update_quotes appends a record to",
+          "it when it is passed after the oracles"
+        ],
+        "accounts": [
+          {
+            "name": "history",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "closeHistory",
+        "docs": [
+          "Has to run before close_strategy, the owner is checked against the strategy"
+        ],
+        "accounts": [
+          {
+            "name": "history",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "getPnl",
+        "docs": [
+          "Returns the strategy PnL through the return data, unrealized PnL is as of the last",
+          "update_quotes that used the oracle"
+        ],
+        "accounts": [
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [],
+        "returns": {
+          "defined": "StrategyPnl"
+        }
+      },
+      {
+        "name": "initializeConfig",
+        "docs": [
+          "Creates the global config, only the program upgrade authority can become the first admin"
+        ],
+        "accounts": [
+          {
+            "name": "config",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "program",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "programData",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "setConfigAdmin",
+        "accounts": [
+          {
+            "name": "config",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": false,
+            "isSigner": true
+          }
+        ],
+        "args": [
+          {
+            "name": "admin",
+            "type": "publicKey"
+          }
+        ]
+      },
+      {
+        "name": "registerMarket",
+        "docs": [
+          "Adds a Phoenix market to the registry, strategies can only be created for",
+          "registered markets"
+        ],
+        "accounts": [
+          {
+            "name": "marketConfig",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "config",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "market",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "oracleType",
+            "type": {
+              "defined": "OracleType"
+            }
+          }
+        ]
+      },
+      {
+        "name": "updateMarketConfig",
+        "docs": [
+          "Replaces the oracles of a registered market and enables or disables it, quotes",
+          "can't be updated on a disabled market"
+        ],
+        "accounts": [
+          {
+            "name": "marketConfig",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "config",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "admin",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "baseOracle",
+            "isMut": false,
+            "isSigner": false
+          },
+          {
+            "name": "quoteOracle",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "oracleType",
+            "type": {
+              "defined": "OracleType"
+            }
+          },
+          {
+            "name": "enabled",
+            "type": "bool"
+          }
+        ]
+      },
+      {
+        "name": "initializePortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": true,
+            "isSigner": true
+          },
+          {
+            "name": "systemProgram",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "addToPortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          },
+          {
+            "name": "phoenixStrategy",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": []
+      },
+      {
+        "name": "removeFromPortfolio",
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": true,
+            "isSigner": false
+          },
+          {
+            "name": "user",
+            "isMut": false,
+            "isSigner": true
+          }
+        ],
+        "args": [
+          {
+            "name": "strategy",
+            "type": "publicKey"
+          }
+        ]
+      },
+      {
+        "name": "updatePortfolio",
+        "docs": [
+          "Updates the quotes of every strategy in the portfolio in one transaction. Remaining",
+          "accounts hold one group per strategy in portfolio order, each group being the",
+          "update_quotes accounts followed by the registered base and quote oracles and optionally",
+          "the strategy history account. Overrides are either empty or one per strategy.",
+          "Strategies that can't quote right now, e.g. paused, rate limited or with a rejected",
+          "oracle, are logged and skipped. Closed strategies are removed from the portfolio by",
+          "close_strategy, but a failing Phoenix CPI or a group with invalid accounts still fails",
+          "the whole transaction"
+        ],
+        "accounts": [
+          {
+            "name": "portfolio",
+            "isMut": false,
+            "isSigner": false
+          }
+        ],
+        "args": [
+          {
+            "name": "fairPriceOverrides",
+            "type": {
+              "vec": {
+                "option": "u64"
+              }
+            }
+          }
+        ]
       }
     ],
     "accounts": [
+      {
+        "name": "globalConfig",
+        "docs": [
+          "Program wide settings, the admin manages the market registry"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "admin",
+              "type": "publicKey"
+            }
+          ]
+        }
+      },
+      {
+        "name": "marketConfig",
+        "docs": [
+          "Registry entry of a Phoenix market, strategies can only be created and quoted on",
+          "enabled markets and always use the registered oracles"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "market",
+              "type": "publicKey"
+            },
+            {
+              "name": "baseOracle",
+              "type": "publicKey"
+            },
+            {
+              "name": "quoteOracle",
+              "type": "publicKey"
+            },
+            {
+              "name": "oracleType",
+              "type": "u8"
+            },
+            {
+              "name": "enabled",
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  6
+                ]
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "strategyHistory",
+        "docs": [
+          "Ring buffer with the last HISTORY_LENGTH updates of a strategy"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "phoenixStrategy",
+              "type": "publicKey"
+            },
+            {
+              "name": "nextIndex",
+              "docs": [
+                "Index the next record is written at"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "numRecords",
+              "type": "u64"
+            },
+            {
+              "name": "records",
+              "type": {
+                "array": [
+                  {
+                    "defined": "UpdateRecord"
+                  },
+                  64
+                ]
+              }
+            }
+          ]
+        }
+      },
+      {
+        "name": "portfolio",
+        "docs": [
+          "Strategies of one owner that a keeper updates together with update_portfolio"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "owner",
+              "type": "publicKey"
+            },
+            {
+              "name": "strategies",
+              "docs": [
+                "Strategies in update order, only the first num_strategies are set"
+              ],
+              "type": {
+                "array": [
+                  "publicKey",
+                  8
+                ]
+              }
+            },
+            {
+              "name": "numStrategies",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "strategyVault",
+        "docs": [
+          "Pools LP capital into a program owned strategy, LP ownership is tracked by the share mint"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "phoenixStrategy",
+              "type": "publicKey"
+            },
+            {
+              "name": "shareMint",
+              "type": "publicKey"
+            },
+            {
+              "name": "quoteAccount",
+              "docs": [
+                "Token accounts of the trader authority that hold the pooled funds outside the seat"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "baseAccount",
+              "type": "publicKey"
+            },
+            {
+              "name": "queuedShares",
+              "docs": [
+                "Shares burned by queued withdrawals that have not been paid out yet"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "withdrawalQueueEnabled",
+              "docs": [
+                "If set to true, withdrawals can be queued while orders are resting"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  7
+                ]
+              }
+            },
+            {
+              "name": "feeRecipient",
+              "docs": [
+                "Wallet whose share token account receives the fees"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "performanceFeeInBps",
+              "docs": [
+                "Share of the NAV per share gain above the high-water mark taken as fee"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "managementFeeInBps",
+              "docs": [
+                "Yearly share of the NAV taken as fee"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "highWaterMark",
+              "docs": [
+                "Highest NAV per share fees were charged at, scaled by BIG_NUMBER, 0 until the first accrual"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "lastFeeUnixTimestamp",
+              "type": "i64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "withdrawalRequest",
+        "docs": [
+          "Withdrawal that was queued while the strategy had orders resting"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "vault",
+              "type": "publicKey"
+            },
+            {
+              "name": "lp",
+              "type": "publicKey"
+            },
+            {
+              "name": "shares",
+              "type": "u64"
+            },
+            {
+              "name": "queuedSlot",
+              "type": "u64"
+            }
+          ]
+        }
+      },
       {
         "name": "phoenixStrategyState",
         "type": {
           "kind": "struct",
           "fields": [
             {
-              "name": "trader",
+              "name": "trader",
+              "type": "publicKey"
+            },
+            {
+              "name": "market",
+              "type": "publicKey"
+            },
+            {
+              "name": "bidOrderSequenceNumber",
+              "type": "u64"
+            },
+            {
+              "name": "bidPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "initialBidSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "askOrderSequenceNumber",
+              "type": "u64"
+            },
+            {
+              "name": "askPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "initialAskSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "lastUpdateSlot",
+              "type": "u64"
+            },
+            {
+              "name": "lastUpdateUnixTimestamp",
+              "type": "i64"
+            },
+            {
+              "name": "quoteEdgeInBps",
+              "docs": [
+                "Number of basis points betweeen quoted price and fair price"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "quoteSizeInQuoteAtoms",
+              "docs": [
+                "Order notional size in quote atoms"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "postOnly",
+              "docs": [
+                "If set to true, the orders will never cross the spread, otherwise they are sent as limit",
+                "orders that can take and use the self-trade behavior"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "priceImprovementBehavior",
+              "docs": [
+                "Determines whether/how to improve BBO"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "paused",
+              "docs": [
+                "If set to true, update_quotes refuses to place orders"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "version",
+              "docs": [
+                "Layout version of the account, 0 for accounts created before versioning"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "hasVault",
+              "docs": [
+                "Set once a vault pools LP capital into the strategy, the fair price can't be overridden"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "padding",
+              "type": {
+                "array": [
+                  "u8",
+                  3
+                ]
+              }
+            },
+            {
+              "name": "guardian",
+              "docs": [
+                "Optional key that is allowed to pause the strategy, Pubkey::default() if unset"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "maxBookDeviationInBps",
+              "docs": [
+                "Maximum distance between the best bid/ask of other traders and the oracle fair price",
+                "before quotes are pulled, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "minSlotsBetweenUpdates",
+              "docs": [
+                "Minimum number of slots between two update_quotes calls, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxOrderNotionalInQuoteAtoms",
+              "docs": [
+                "Maximum notional of a single order in quote atoms, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxCancelReplaceCycles",
+              "docs": [
+                "Maximum number of cancel-replace cycles placing orders per window, cancels alone are",
+                "never limited, 0 disables the check"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceWindowInSlots",
+              "docs": [
+                "Length of the cancel-replace window in slots"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceWindowStartSlot",
+              "type": "u64"
+            },
+            {
+              "name": "cancelReplaceCyclesInWindow",
+              "type": "u64"
+            },
+            {
+              "name": "minQuotePriceInTicks",
+              "docs": [
+                "Lowest price in ticks we are allowed to quote at, 0 disables the bound"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "maxQuotePriceInTicks",
+              "docs": [
+                "Highest price in ticks we are allowed to quote at, 0 disables the bound"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "heartbeatInSlots",
+              "docs": [
+                "Number of slots without an update after which anyone can cancel our quotes, 0 disables"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "selfTradeBehavior",
+              "docs": [
+                "What happens when a limit order would match against one of our own orders"
+              ],
+              "type": "u8"
+            },
+            {
+              "name": "failSilentlyOnInsufficientFunds",
+              "docs": [
+                "If set to true, a side without enough funds is skipped instead of failing the transaction"
+              ],
+              "type": "bool"
+            },
+            {
+              "name": "executionPadding",
+              "type": {
+                "array": [
+                  "u8",
+                  6
+                ]
+              }
+            },
+            {
+              "name": "quoteReserveInQuoteAtoms",
+              "docs": [
+                "Quote atoms that are never committed to orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "baseReserveInBaseAtoms",
+              "docs": [
+                "Base atoms that are never committed to orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "minOrderSizeInBaseLots",
+              "docs": [
+                "Sides that can't be funded for at least this size are skipped"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "marginInTicks",
+              "docs": [
+                "Minimum distance in ticks between the fair price and the BBO for Ubermensch mode to keep",
+                "quoting at the BBO"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "operator",
+              "docs": [
+                "Optional key that can update and cancel quotes but not change parameters, move funds or",
+                "override the oracle fair price, Pubkey::default() if unset"
+              ],
               "type": "publicKey"
             },
             {
-              "name": "market",
+              "name": "owner",
+              "docs": [
+                "Wallet that created the strategy, differs from the trader when the program holds the seat"
+              ],
               "type": "publicKey"
             },
             {
-              "name": "bidOrderSequenceNumber",
+              "name": "seat",
+              "docs": [
+                "Phoenix seat of the trader on the market"
+              ],
+              "type": "publicKey"
+            },
+            {
+              "name": "numBidFills",
               "type": "u64"
             },
             {
-              "name": "bidPriceInTicks",
+              "name": "numAskFills",
               "type": "u64"
             },
             {
-              "name": "initialBidSizeInBaseLots",
+              "name": "baseLotsBought",
               "type": "u64"
             },
             {
-              "name": "askOrderSequenceNumber",
+              "name": "baseLotsSold",
               "type": "u64"
             },
             {
-              "name": "askPriceInTicks",
+              "name": "quoteAtomsPaid",
               "type": "u64"
             },
             {
-              "name": "initialAskSizeInBaseLots",
+              "name": "quoteAtomsReceived",
               "type": "u64"
             },
             {
-              "name": "lastUpdateSlot",
+              "name": "averageBuyPriceInTicks",
+              "docs": [
+                "Volume weighted average price of our filled bids"
+              ],
               "type": "u64"
             },
             {
-              "name": "lastUpdateUnixTimestamp",
+              "name": "averageSellPriceInTicks",
+              "docs": [
+                "Volume weighted average price of our filled asks"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "numCancelReplaceCycles",
+              "docs": [
+                "Total number of updates that cancelled or placed orders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "quoteLotsFreeBaseline",
+              "docs": [
+                "Free lots of the trader right after the tracked orders were placed, moved by seat",
+                "deposits and withdrawals and by the fills booked since"
+              ],
               "type": "i64"
             },
             {
-              "name": "quoteEdgeInBps",
+              "name": "baseLotsFreeBaseline",
+              "type": "i64"
+            },
+            {
+              "name": "netPositionInBaseLots",
               "docs": [
-                "Number of basis points betweeen quoted price and fair price"
+                "Positive when long, negative when short"
+              ],
+              "type": "i64"
+            },
+            {
+              "name": "averageEntryPriceInTicks",
+              "docs": [
+                "Average price the open position was entered at after fees, 0 when flat"
               ],
               "type": "u64"
             },
             {
-              "name": "quoteSizeInQuoteAtoms",
+              "name": "realizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "unrealizedPnlInQuoteAtoms",
               "docs": [
-                "Order notional size in quote atoms"
+                "Open position marked against the oracle fair price of the last update"
               ],
+              "type": "i64"
+            },
+            {
+              "name": "lastMarkPriceInTicks",
               "type": "u64"
             },
             {
-              "name": "postOnly",
+              "name": "positionCostInQuoteAtoms",
               "docs": [
-                "If set to true, the orders will never cross the spread"
+                "Quote atoms paid for the open position including fees, negative for the quote atoms",
+                "received when short"
               ],
-              "type": "bool"
+              "type": "i64"
+            }
+          ]
+        }
+      }
+    ],
+    "types": [
+      {
+        "name": "UpdateRecord",
+        "docs": [
+          "What update_quotes saw and did in one call"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "slot",
+              "type": "u64"
             },
             {
-              "name": "priceImprovementBehavior",
+              "name": "fairPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "oracleConfidenceInTicks",
               "docs": [
-                "Determines whether/how to improve BBO"
+                "Confidence interval of the base oracle price, 0 when the fair price was overridden"
               ],
-              "type": "u8"
+              "type": "u64"
             },
             {
-              "name": "padding",
+              "name": "bestBidInTicks",
+              "docs": [
+                "Best prices of other traders"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "bestAskInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "bidPriceInTicks",
+              "docs": [
+                "Our resting orders after the update, 0 if there is none"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "bidSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "askPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "askSizeInBaseLots",
+              "type": "u64"
+            },
+            {
+              "name": "quoteAtomsHeld",
+              "docs": [
+                "Trader inventory in the token accounts and the Phoenix seat"
+              ],
+              "type": "u64"
+            },
+            {
+              "name": "baseAtomsHeld",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "StrategyPnl",
+        "docs": [
+          "PnL of a strategy as returned by get_pnl"
+        ],
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "realizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "unrealizedPnlInQuoteAtoms",
+              "type": "i64"
+            },
+            {
+              "name": "netPositionInBaseLots",
+              "type": "i64"
+            },
+            {
+              "name": "averageEntryPriceInTicks",
+              "type": "u64"
+            },
+            {
+              "name": "lastMarkPriceInTicks",
+              "type": "u64"
+            }
+          ]
+        }
+      },
+      {
+        "name": "StrategyParams",
+        "type": {
+          "kind": "struct",
+          "fields": [
+            {
+              "name": "quoteEdgeInBps",
               "type": {
-                "array": [
-                  "u8",
-                  6
-                ]
+                "option": "u64"
+              }
+            },
+            {
+              "name": "quoteSizeInQuoteAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "priceImprovementBehavior",
+              "type": {
+                "option": {
+                  "defined": "PriceImprovementBehavior"
+                }
+              }
+            },
+            {
+              "name": "postOnly",
+              "type": {
+                "option": "bool"
+              }
+            },
+            {
+              "name": "marginInTicks",
+              "type": {
+                "option": "u64"
               }
             }
           ]
         }
-      }
-    ],
-    "types": [
+      },
       {
-        "name": "OrderParams",
+        "name": "RiskParams",
         "type": {
           "kind": "struct",
           "fields": [
             {
-              "name": "fairPriceInQuoteAtomsPerRawBaseUnit",
-              "type": "u64"
+              "name": "maxBookDeviationInBps",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "minSlotsBetweenUpdates",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "maxOrderNotionalInQuoteAtoms",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "maxCancelReplaceCycles",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "cancelReplaceWindowInSlots",
+              "type": {
+                "option": "u64"
+              }
+            },
+            {
+              "name": "minQuotePriceInTicks",
+              "type": {
+                "option": "u64"
+              }
             },
             {
-              "name": "strategyParams",
+              "name": "maxQuotePriceInTicks",
               "type": {
-                "defined": "StrategyParams"
+                "option": "u64"
               }
             },
             {
-              "name": "useOracle",
-              "type": "bool"
+              "name": "heartbeatInSlots",
+              "type": {
+                "option": "u64"
+              }
             }
           ]
         }
       },
       {
-        "name": "StrategyParams",
+        "name": "ExecutionParams",
         "type": {
           "kind": "struct",
           "fields": [
             {
-              "name": "quoteEdgeInBps",
+              "name": "selfTradeBehavior",
               "type": {
-                "option": "u64"
+                "option": {
+                  "defined": "SelfTradeBehavior"
+                }
               }
             },
             {
-              "name": "quoteSizeInQuoteAtoms",
+              "name": "failSilentlyOnInsufficientFunds",
+              "type": {
+                "option": "bool"
+              }
+            },
+            {
+              "name": "quoteReserveInQuoteAtoms",
               "type": {
                 "option": "u64"
               }
             },
             {
-              "name": "priceImprovementBehavior",
+              "name": "baseReserveInBaseAtoms",
               "type": {
-                "option": {
-                  "defined": "PriceImprovementBehavior"
-                }
+                "option": "u64"
               }
             },
             {
-              "name": "postOnly",
+              "name": "minOrderSizeInBaseLots",
               "type": {
-                "option": "bool"
+                "option": "u64"
               }
             }
           ]
         }
       },
+      {
+        "name": "OracleType",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Pyth"
+            }
+          ]
+        }
+      },
+      {
+        "name": "QuoteSide",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Bid"
+            },
+            {
+              "name": "Ask"
+            }
+          ]
+        }
+      },
+      {
+        "name": "OracleRejectionReason",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "StaleSlot"
+            },
+            {
+              "name": "NotTrading"
+            },
+            {
+              "name": "NegativePrice"
+            },
+            {
+              "name": "WideConfidence"
+            }
+          ]
+        }
+      },
       {
         "name": "PriceStatus",
         "type": {
@@ -744,6 +5909,281 @@ export type UberMm = {
             }
           ]
         }
+      },
+      {
+        "name": "SelfTradeBehavior",
+        "type": {
+          "kind": "enum",
+          "variants": [
+            {
+              "name": "Abort"
+            },
+            {
+              "name": "CancelProvide"
+            },
+            {
+              "name": "DecrementTake"
+            }
+          ]
+        }
+      }
+    ],
+    "events": [
+      {
+        "name": "StrategyInitialized",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "owner",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "trader",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "market",
+            "type": "publicKey",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "StrategyParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "StrategyParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "RiskParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "RiskParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "ExecutionParamsChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "params",
+            "type": {
+              "defined": "ExecutionParams"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OperatorChanged",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "operator",
+            "type": {
+              "option": "publicKey"
+            },
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "QuotePlaced",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "side",
+            "type": {
+              "defined": "QuoteSide"
+            },
+            "index": false
+          },
+          {
+            "name": "priceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "sizeInBaseLots",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "orderSequenceNumber",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OrdersCancelled",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "orderSequenceNumbers",
+            "type": {
+              "vec": "u64"
+            },
+            "index": false
+          },
+          {
+            "name": "cancelledAll",
+            "type": "bool",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "FillDetected",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "side",
+            "type": {
+              "defined": "QuoteSide"
+            },
+            "index": false
+          },
+          {
+            "name": "priceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "baseLots",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "quoteAtoms",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "OracleRejected",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "oracle",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "reason",
+            "type": {
+              "defined": "OracleRejectionReason"
+            },
+            "index": false
+          },
+          {
+            "name": "price",
+            "type": "i64",
+            "index": false
+          },
+          {
+            "name": "confidence",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "validSlot",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "currentSlot",
+            "type": "u64",
+            "index": false
+          }
+        ]
+      },
+      {
+        "name": "CircuitBreakerTripped",
+        "fields": [
+          {
+            "name": "strategy",
+            "type": "publicKey",
+            "index": false
+          },
+          {
+            "name": "bestBidInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "bestAskInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "fairPriceInTicks",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "deviationInBps",
+            "type": "u64",
+            "index": false
+          },
+          {
+            "name": "maxBookDeviationInBps",
+            "type": "u64",
+            "index": false
+          }
+        ]
       }
     ],
     "errors": [
@@ -782,6 +6222,134 @@ export type UberMm = {
       {
         "code": 6008,
         "name": "PythConfidence"
+      },
+      {
+        "code": 6009,
+        "name": "StrategyPaused"
+      },
+      {
+        "code": 6010,
+        "name": "InvalidPauseAuthority"
+      },
+      {
+        "code": 6011,
+        "name": "UpdateTooFrequent"
+      },
+      {
+        "code": 6012,
+        "name": "OrderNotionalTooLarge"
+      },
+      {
+        "code": 6013,
+        "name": "TooManyCancelReplaceCycles"
+      },
+      {
+        "code": 6014,
+        "name": "TraderNotProgramOwned"
+      },
+      {
+        "code": 6015,
+        "name": "StrategyNotStale"
+      },
+      {
+        "code": 6016,
+        "name": "InvalidStrategyAuthority"
+      },
+      {
+        "code": 6017,
+        "name": "InvalidStrategyAccount"
+      },
+      {
+        "code": 6018,
+        "name": "StrategyNeedsMigration"
+      },
+      {
+        "code": 6019,
+        "name": "InvalidVaultMint"
+      },
+      {
+        "code": 6020,
+        "name": "InvalidVaultAmount"
+      },
+      {
+        "code": 6021,
+        "name": "InvalidVaultTokenAccount"
+      },
+      {
+        "code": 6022,
+        "name": "VaultOrdersResting"
+      },
+      {
+        "code": 6023,
+        "name": "WithdrawalQueueDisabled"
+      },
+      {
+        "code": 6024,
+        "name": "StrategyHasVault"
+      },
+      {
+        "code": 6025,
+        "name": "InvalidVaultFees"
+      },
+      {
+        "code": 6026,
+        "name": "PortfolioFull"
+      },
+      {
+        "code": 6027,
+        "name": "PortfolioStrategyExists"
+      },
+      {
+        "code": 6028,
+        "name": "PortfolioStrategyNotFound"
+      },
+      {
+        "code": 6029,
+        "name": "InvalidPortfolioAccounts"
+      },
+      {
+        "code": 6030,
+        "name": "InvalidConfigAdmin"
+      },
+      {
+        "code": 6031,
+        "name": "MarketDisabled"
+      },
+      {
+        "code": 6032,
+        "name": "InvalidOracleAccount"
+      },
+      {
+        "code": 6033,
+        "name": "InvalidPriceImprovementBehavior"
+      },
+      {
+        "code": 6034,
+        "name": "InvalidSelfTradeBehavior"
+      },
+      {
+        "code": 6035,
+        "name": "InvalidOracleType"
+      },
+      {
+        "code": 6036,
+        "name": "InvalidHistoryAccount"
+      },
+      {
+        "code": 6037,
+        "name": "FairPriceOverrideNotAllowed"
+      },
+      {
+        "code": 6038,
+        "name": "VaultNavPerShareOverflow"
+      },
+      {
+        "code": 6039,
+        "name": "StrategyHasHistory"
+      },
+      {
+        "code": 6040,
+        "name": "UnsupportedStrategyVersion"
       }
     ]
   };
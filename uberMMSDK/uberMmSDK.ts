@@ -2,7 +2,7 @@ import { AnchorProvider, Program } from "@coral-xyz/anchor";
 import * as anchor from "@coral-xyz/anchor";
 import { UberMm, UberMmIDL } from "./uberMmIDL";
 import { PublicKey, Connection, Keypair, SystemProgram, Account, sendAndConfirmTransaction, Transaction } from "@solana/web3.js";
-import { PHOENIX_PROGRAM_ID, UBER_MM_PROGRAM_ID } from "./consts";
+import { PHOENIX_PROGRAM_ID, PHOENIX_SEAT_MANAGER_PROGRAM_ID, UBER_MM_PROGRAM_ID } from "./consts";
 import { createPhoenixClient, getTokenBalance } from "./helpers";
 import * as Phoenix from "@ellipsis-labs/phoenix-sdk";
 import { Wallet } from "./consts";
@@ -50,47 +50,817 @@ export class UberMmSDK {
         return programAccounts.map((account: any) => {return account.pubkey});
     }
 
+    // initializes the strategy and claims its seat through the Phoenix seat manager in one transaction
     public async initializeStrategyState(
         params: MMParams,
         marketAddress: PublicKey,
+        guardian: PublicKey | null = null, // optional key allowed to pause the strategy
     ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .initializeWithSeat(
+                new BN(params.quoteEdgeInBps),
+                new BN(params.quoteSizeInQuoteAtoms),
+                0,
+                false,
+                guardian,
+            )
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                ...(await this.getClaimSeatAccounts(marketAddress, this.wallet.publicKey)),
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    private async getClaimSeatAccounts(
+        marketAddress: PublicKey,
+        trader: PublicKey,
+    ) {
         let phoenixClient = await createPhoenixClient(this.connection, marketAddress);
         const phoenixMarket = phoenixClient.marketStates.get(
             marketAddress.toBase58()
         );
-        let [phoenixStrategy, _] = PublicKey.findProgramAddressSync(
+        let [seatManager, _] = PublicKey.findProgramAddressSync(
+            [marketAddress.toBuffer()],
+            PHOENIX_SEAT_MANAGER_PROGRAM_ID
+        );
+        let [seatDepositCollector, __] = PublicKey.findProgramAddressSync(
+            [marketAddress.toBuffer(), anchor.utils.bytes.utf8.encode("deposit")],
+            PHOENIX_SEAT_MANAGER_PROGRAM_ID
+        );
+        return {
+            seatManagerProgram: PHOENIX_SEAT_MANAGER_PROGRAM_ID,
+            phoenixProgram: Phoenix.PROGRAM_ID,
+            logAuthority: Phoenix.getLogAuthority(),
+            market: marketAddress,
+            marketConfig: getMarketConfigAddress(marketAddress),
+            seatManager: seatManager,
+            seatDepositCollector: seatDepositCollector,
+            seat: phoenixMarket.getSeatAddress(trader),
+            systemProgram: SystemProgram.programId,
+        };
+    }
+
+    // the strategy trader authority holds the seat, which is claimed through the Phoenix seat manager
+    public async initializeProgramOwnedStrategy(
+        params: MMParams,
+        marketAddress: PublicKey,
+        guardian: PublicKey | null = null,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        let traderAuthority = getTraderAuthorityAddress(phoenixStrategy);
+        return await this.program.methods
+            .initializeProgramOwned(
+                new BN(params.quoteEdgeInBps),
+                new BN(params.quoteSizeInQuoteAtoms),
+                0,
+                false,
+                guardian,
+            )
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                traderAuthority: traderAuthority,
+                ...(await this.getClaimSeatAccounts(marketAddress, traderAuthority)),
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // moves tokens held by the trader authority of a program owned strategy back to the wallet,
+    // null amount transfers the whole balance
+    public async withdrawTraderFunds(
+        marketAddress: PublicKey,
+        sourceTokenAccount: PublicKey,
+        destinationTokenAccount: PublicKey,
+        amount: number | null,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .withdrawTraderFunds(optionalBN(amount))
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+                vault: getStrategyVaultAddress("vault", phoenixStrategy),
+                traderAuthority: getTraderAuthorityAddress(phoenixStrategy),
+                sourceAccount: sourceTokenAccount,
+                destinationAccount: destinationTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // pools LP funds into the wallet's program owned strategy
+    public async initializeVault(
+        marketAddress: PublicKey,
+        withdrawalQueueEnabled: boolean,
+        performanceFeeInBps: number,
+        managementFeeInBps: number,
+        feeRecipient: PublicKey = this.wallet.publicKey, // wallet whose share token account receives the fees
+    ): Promise<string> {
+        let phoenixClient = await createPhoenixClient(this.connection, marketAddress);
+        const phoenixMarket = phoenixClient.marketStates.get(
+            marketAddress.toBase58()
+        );
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        let marketConfigAddress = getMarketConfigAddress(marketAddress);
+        let marketConfig = await this.program.account.marketConfig.fetch(marketConfigAddress);
+        return await this.program.methods
+            .initializeVault(
+                withdrawalQueueEnabled,
+                feeRecipient,
+                new BN(performanceFeeInBps),
+                new BN(managementFeeInBps),
+            )
+            .accounts({
+                vault: getStrategyVaultAddress("vault", phoenixStrategy),
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+                marketConfig: marketConfigAddress,
+                traderAuthority: getTraderAuthorityAddress(phoenixStrategy),
+                shareMint: getStrategyVaultAddress("shares", phoenixStrategy),
+                quoteMint: phoenixMarket.data.header.quoteParams.mintKey,
+                baseMint: phoenixMarket.data.header.baseParams.mintKey,
+                quoteAccount: getStrategyVaultAddress("quote", phoenixStrategy),
+                baseAccount: getStrategyVaultAddress("base", phoenixStrategy),
+                baseOracle: marketConfig.baseOracle,
+                quoteOracle: marketConfig.quoteOracle,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async depositToVault(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        lpQuoteTokenAccount: PublicKey,
+        lpBaseTokenAccount: PublicKey,
+        lpShareTokenAccount: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+        quoteAtoms: number,
+        baseAtoms: number,
+    ): Promise<string> {
+        return await this.program.methods
+            .depositToVault(new BN(quoteAtoms), new BN(baseAtoms))
+            .accounts({
+                fees: await this.getCrystallizeFeesAccounts(owner, marketAddress, feeRecipientShareTokenAccount),
+                lp: this.wallet.publicKey,
+                lpQuoteAccount: lpQuoteTokenAccount,
+                lpBaseAccount: lpBaseTokenAccount,
+                lpShareAccount: lpShareTokenAccount,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // mints the fees accrued since the last crystallization, anyone can call it
+    public async crystallizeFees(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+    ): Promise<string> {
+        return await this.program.methods
+            .crystallizeFees()
+            .accounts(await this.getCrystallizeFeesAccounts(owner, marketAddress, feeRecipientShareTokenAccount))
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    private async getCrystallizeFeesAccounts(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+    ) {
+        let phoenixStrategy = getPhoenixStrategyAddress(owner, marketAddress);
+        let vault = getStrategyVaultAddress("vault", phoenixStrategy);
+        let vaultState = await this.program.account.strategyVault.fetch(vault);
+        let marketConfigAddress = getMarketConfigAddress(marketAddress);
+        let marketConfig = await this.program.account.marketConfig.fetch(marketConfigAddress);
+        return {
+            vault: vault,
+            phoenixStrategy: phoenixStrategy,
+            traderAuthority: getTraderAuthorityAddress(phoenixStrategy),
+            market: marketAddress,
+            shareMint: vaultState.shareMint,
+            feeRecipientAccount: feeRecipientShareTokenAccount,
+            quoteAccount: vaultState.quoteAccount,
+            baseAccount: vaultState.baseAccount,
+            marketConfig: marketConfigAddress,
+            baseOracle: marketConfig.baseOracle,
+            quoteOracle: marketConfig.quoteOracle,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+    }
+
+    // fails while the strategy has orders resting, queueWithdrawal can be used instead
+    public async withdrawFromVault(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        lpQuoteTokenAccount: PublicKey,
+        lpBaseTokenAccount: PublicKey,
+        lpShareTokenAccount: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+        shares: number,
+    ): Promise<string> {
+        let payout = await this.getVaultPayoutAccounts(
+            owner, marketAddress, lpQuoteTokenAccount, lpBaseTokenAccount, feeRecipientShareTokenAccount
+        );
+        return await this.program.methods
+            .withdrawFromVault(new BN(shares))
+            .accounts({
+                payout: payout,
+                lp: this.wallet.publicKey,
+                lpShareAccount: lpShareTokenAccount,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async queueWithdrawal(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        lpShareTokenAccount: PublicKey,
+        shares: number,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(owner, marketAddress);
+        let vault = getStrategyVaultAddress("vault", phoenixStrategy);
+        return await this.program.methods
+            .queueWithdrawal(new BN(shares))
+            .accounts({
+                vault: vault,
+                withdrawalRequest: getWithdrawalRequestAddress(vault, this.wallet.publicKey),
+                shareMint: getStrategyVaultAddress("shares", phoenixStrategy),
+                lp: this.wallet.publicKey,
+                lpShareAccount: lpShareTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // anyone can process a queued withdrawal once the strategy has no orders resting
+    public async processWithdrawal(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        lp: PublicKey,
+        lpQuoteTokenAccount: PublicKey,
+        lpBaseTokenAccount: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+    ): Promise<string> {
+        let payout = await this.getVaultPayoutAccounts(
+            owner, marketAddress, lpQuoteTokenAccount, lpBaseTokenAccount, feeRecipientShareTokenAccount
+        );
+        return await this.program.methods
+            .processWithdrawal()
+            .accounts({
+                payout: payout,
+                withdrawalRequest: getWithdrawalRequestAddress(payout.fees.vault, lp),
+                lp: lp,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    private async getVaultPayoutAccounts(
+        owner: PublicKey,
+        marketAddress: PublicKey,
+        lpQuoteTokenAccount: PublicKey,
+        lpBaseTokenAccount: PublicKey,
+        feeRecipientShareTokenAccount: PublicKey,
+    ) {
+        let phoenixClient = await createPhoenixClient(this.connection, marketAddress);
+        const phoenixMarket = phoenixClient.marketStates.get(
+            marketAddress.toBase58()
+        );
+        return {
+            fees: await this.getCrystallizeFeesAccounts(owner, marketAddress, feeRecipientShareTokenAccount),
+            phoenixProgram: Phoenix.PROGRAM_ID,
+            logAuthority: Phoenix.getLogAuthority(),
+            quoteVault: phoenixMarket.data.header.quoteParams.vaultKey,
+            baseVault: phoenixMarket.data.header.baseParams.vaultKey,
+            lpQuoteAccount: lpQuoteTokenAccount,
+            lpBaseAccount: lpBaseTokenAccount,
+        };
+    }
+
+    private async getStrategyTrader(phoenixStrategy: PublicKey): Promise<PublicKey> {
+        let strategy = await this.program.account.phoenixStrategyState.fetch(phoenixStrategy);
+        return strategy.trader;
+    }
+
+    public async pauseStrategy(
+        marketAddress: PublicKey,
+        owner: PublicKey = this.wallet.publicKey, // pausing a wallet owned strategy as guardian leaves the orders resting
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(owner, marketAddress);
+        return await this.program.methods
+            .pause()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                authority: this.wallet.publicKey,
+                owner: owner,
+                trader: await this.getStrategyTrader(phoenixStrategy),
+                phoenixProgram: Phoenix.PROGRAM_ID,
+                logAuthority: Phoenix.getLogAuthority(),
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async resumeStrategy(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .resume()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async migrateStrategy(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .migrateStrategy()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async cancelAllQuotes(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .cancelAllQuotes()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                authority: this.wallet.publicKey,
+                trader: await this.getStrategyTrader(phoenixStrategy),
+                phoenixProgram: Phoenix.PROGRAM_ID,
+                logAuthority: Phoenix.getLogAuthority(),
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // null lots deposits the whole token account balance
+    public async depositToSeat(
+        marketAddress: PublicKey,
+        makerQuoteTokenAccount: PublicKey,
+        makerBaseTokenAccount: PublicKey,
+        quoteLots: number | null,
+        baseLots: number | null,
+    ): Promise<string> {
+        return await this.program.methods
+            .depositToSeat(optionalBN(quoteLots), optionalBN(baseLots))
+            .accounts(await this.getSeatFundsAccounts(marketAddress, makerQuoteTokenAccount, makerBaseTokenAccount))
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // null lots withdraws all free funds
+    public async withdrawFromSeat(
+        marketAddress: PublicKey,
+        makerQuoteTokenAccount: PublicKey,
+        makerBaseTokenAccount: PublicKey,
+        quoteLots: number | null,
+        baseLots: number | null,
+    ): Promise<string> {
+        return await this.program.methods
+            .withdrawFromSeat(optionalBN(quoteLots), optionalBN(baseLots))
+            .accounts(await this.getSeatFundsAccounts(marketAddress, makerQuoteTokenAccount, makerBaseTokenAccount))
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    private async getSeatFundsAccounts(
+        marketAddress: PublicKey,
+        makerQuoteTokenAccount: PublicKey,
+        makerBaseTokenAccount: PublicKey,
+    ) {
+        let phoenixClient = await createPhoenixClient(this.connection, marketAddress);
+        const phoenixMarket = phoenixClient.marketStates.get(
+            marketAddress.toBase58()
+        );
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        let trader = await this.getStrategyTrader(phoenixStrategy);
+        return {
+            phoenixStrategy: phoenixStrategy,
+            user: this.wallet.publicKey,
+            trader: trader,
+            phoenixProgram: Phoenix.PROGRAM_ID,
+            logAuthority: Phoenix.getLogAuthority(),
+            market: marketAddress,
+            seat: phoenixMarket.getSeatAddress(trader),
+            quoteAccount: makerQuoteTokenAccount,
+            baseAccount: makerBaseTokenAccount,
+            quoteVault: phoenixMarket.data.header.quoteParams.vaultKey,
+            baseVault: phoenixMarket.data.header.baseParams.vaultKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        };
+    }
+
+    // cancels all orders, withdraws the seat's free funds and reclaims the strategy rent
+    public async closeStrategy(
+        marketAddress: PublicKey,
+        makerQuoteTokenAccount: PublicKey,
+        makerBaseTokenAccount: PublicKey,
+    ): Promise<string> {
+        let phoenixClient = await createPhoenixClient(this.connection, marketAddress);
+        const phoenixMarket = phoenixClient.marketStates.get(
+            marketAddress.toBase58()
+        );
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .closeStrategy()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                vault: getStrategyVaultAddress("vault", phoenixStrategy),
+                history: getStrategyHistoryAddress(phoenixStrategy),
+                portfolio: getPortfolioAddress(this.wallet.publicKey),
+                trader: await this.getStrategyTrader(phoenixStrategy),
+                phoenixProgram: Phoenix.PROGRAM_ID,
+                logAuthority: Phoenix.getLogAuthority(),
+                market: marketAddress,
+                quoteAccount: makerQuoteTokenAccount,
+                baseAccount: makerBaseTokenAccount,
+                quoteVault: phoenixMarket.data.header.quoteParams.vaultKey,
+                baseVault: phoenixMarket.data.header.baseParams.vaultKey,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async setStrategyParams(
+        marketAddress: PublicKey,
+        params: MMParams,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .setStrategyParams({
+                quoteEdgeInBps: new BN(params.quoteEdgeInBps),
+                quoteSizeInQuoteAtoms: new BN(params.quoteSizeInQuoteAtoms),
+                priceImprovementBehavior: priceImprovementBehaviors[params.priceImprovementBehavior],
+                postOnly: params.postOnly,
+                marginInTicks: new BN(params.margin),
+            })
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // the operator can update and cancel quotes but can't change params or move funds
+    public async setOperator(
+        marketAddress: PublicKey,
+        operator: PublicKey | null,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .setOperator(operator)
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async setRiskParams(
+        marketAddress: PublicKey,
+        params: RiskParams,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .setRiskParams({
+                maxBookDeviationInBps: optionalBN(params.maxBookDeviationInBps),
+                minSlotsBetweenUpdates: optionalBN(params.minSlotsBetweenUpdates),
+                maxOrderNotionalInQuoteAtoms: optionalBN(params.maxOrderNotionalInQuoteAtoms),
+                maxCancelReplaceCycles: optionalBN(params.maxCancelReplaceCycles),
+                cancelReplaceWindowInSlots: optionalBN(params.cancelReplaceWindowInSlots),
+                minQuotePriceInTicks: optionalBN(params.minQuotePriceInTicks),
+                maxQuotePriceInTicks: optionalBN(params.maxQuotePriceInTicks),
+                heartbeatInSlots: optionalBN(params.heartbeatInSlots),
+            })
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async setExecutionParams(
+        marketAddress: PublicKey,
+        params: ExecutionParams,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .setExecutionParams({
+                selfTradeBehavior: params.selfTradeBehavior ?? null,
+                failSilentlyOnInsufficientFunds: params.failSilentlyOnInsufficientFunds ?? null,
+                quoteReserveInQuoteAtoms: optionalBN(params.quoteReserveInQuoteAtoms),
+                baseReserveInBaseAtoms: optionalBN(params.baseReserveInBaseAtoms),
+                minOrderSizeInBaseLots: optionalBN(params.minOrderSizeInBaseLots),
+            })
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // permissionless, only works for strategies whose trader is the program derived trader authority
+    public async cancelIfStale(
+        phoenixStrategy: PublicKey,
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let [traderAuthority, _] = PublicKey.findProgramAddressSync(
             [
-                anchor.utils.bytes.utf8.encode("phoenix"),
-                this.wallet.publicKey.toBuffer(),
-                marketAddress.toBuffer(),
+                anchor.utils.bytes.utf8.encode("trader"),
+                phoenixStrategy.toBuffer(),
             ],
             UBER_MM_PROGRAM_ID
         );
-        try {
-        const tx = await this.program.methods
-                .initialize(
-                    new BN(params.quoteEdgeInBps),
-                    new BN(params.quoteSizeInQuoteAtoms),
-                    0,
-                    false,
-                )
-                .accounts({
-                    phoenixStrategy: phoenixStrategy,
-                    user: this.wallet.publicKey,
-                    market: marketAddress,
-                    systemProgram: SystemProgram.programId,
-                })
-                .signers([this.wallet])
-                .rpc();
-            } catch(e){console.log(e)}
-        try {
-            let seatTx = phoenixMarket.createRequestSeatInstruction(this.wallet.publicKey, this.wallet.publicKey);
-            let res = await sendAndConfirmTransaction(this.connection, new Transaction().add(seatTx), [this.wallet]); } catch(e){console.log(e)}
-        try {
-            let claimSeatTx = await Phoenix.getClaimSeatIx(marketAddress, this.wallet.publicKey);
-            let res = await sendAndConfirmTransaction(this.connection, new Transaction().add(claimSeatTx), [this.wallet]);
-        }catch(e){console.log(e)}
-        return;
+        return await this.program.methods
+            .cancelIfStale()
+            .accounts({
+                phoenixStrategy: phoenixStrategy,
+                traderAuthority: traderAuthority,
+                phoenixProgram: Phoenix.PROGRAM_ID,
+                logAuthority: Phoenix.getLogAuthority(),
+                market: marketAddress,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async initializeHistory(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .initializeHistory()
+            .accounts({
+                history: getStrategyHistoryAddress(phoenixStrategy),
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async closeHistory(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        let phoenixStrategy = getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress);
+        return await this.program.methods
+            .closeHistory()
+            .accounts({
+                history: getStrategyHistoryAddress(phoenixStrategy),
+                phoenixStrategy: phoenixStrategy,
+                user: this.wallet.publicKey,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // update records from oldest to newest
+    public async getHistory(
+        marketAddress: PublicKey,
+        owner: PublicKey = this.wallet.publicKey,
+    ) {
+        let phoenixStrategy = getPhoenixStrategyAddress(owner, marketAddress);
+        let history = await this.program.account.strategyHistory.fetch(
+            getStrategyHistoryAddress(phoenixStrategy)
+        );
+        let numRecords = history.numRecords.toNumber();
+        let start = numRecords < history.records.length ? 0 : history.nextIndex.toNumber();
+        let records = [];
+        for (let i = 0; i < numRecords; i++) {
+            records.push(history.records[(start + i) % history.records.length]);
+        }
+        return records;
+    }
+
+    // realized and unrealized PnL in quote atoms, unrealized is marked at the last oracle update
+    public async getPnl(
+        marketAddress: PublicKey,
+        owner: PublicKey = this.wallet.publicKey,
+    ) {
+        return await this.program.methods
+            .getPnl()
+            .accounts({
+                phoenixStrategy: getPhoenixStrategyAddress(owner, marketAddress),
+            })
+            .view();
+    }
+
+    // decodes the program events as they are logged, e.g. "QuotePlaced" or "FillDetected"
+    public addEventListener(
+        eventName: string,
+        callback: (event: any, slot: number) => void,
+    ): number {
+        return this.program.addEventListener(eventName, callback);
+    }
+
+    public async removeEventListener(listener: number): Promise<void> {
+        await this.program.removeEventListener(listener);
+    }
+
+    // the wallet has to be the program upgrade authority
+    public async initializeConfig(): Promise<string> {
+        let [programData, _] = PublicKey.findProgramAddressSync(
+            [UBER_MM_PROGRAM_ID.toBuffer()],
+            new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+        );
+        return await this.program.methods
+            .initializeConfig()
+            .accounts({
+                config: getGlobalConfigAddress(),
+                admin: this.wallet.publicKey,
+                program: UBER_MM_PROGRAM_ID,
+                programData: programData,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async setConfigAdmin(
+        admin: PublicKey,
+    ): Promise<string> {
+        return await this.program.methods
+            .setConfigAdmin(admin)
+            .accounts({
+                config: getGlobalConfigAddress(),
+                admin: this.wallet.publicKey,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // oracles default to the Pyth feeds in marketsToPyth
+    public async registerMarket(
+        marketAddress: PublicKey,
+        baseOracle: PublicKey = new PublicKey(marketsToPyth[marketAddress.toBase58()][0]),
+        quoteOracle: PublicKey = new PublicKey(marketsToPyth[marketAddress.toBase58()][1]),
+    ): Promise<string> {
+        return await this.program.methods
+            .registerMarket({ pyth: {} })
+            .accounts({
+                marketConfig: getMarketConfigAddress(marketAddress),
+                config: getGlobalConfigAddress(),
+                admin: this.wallet.publicKey,
+                market: marketAddress,
+                baseOracle: baseOracle,
+                quoteOracle: quoteOracle,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async updateMarketConfig(
+        marketAddress: PublicKey,
+        enabled: boolean,
+        baseOracle: PublicKey,
+        quoteOracle: PublicKey,
+    ): Promise<string> {
+        return await this.program.methods
+            .updateMarketConfig({ pyth: {} }, enabled)
+            .accounts({
+                marketConfig: getMarketConfigAddress(marketAddress),
+                config: getGlobalConfigAddress(),
+                admin: this.wallet.publicKey,
+                baseOracle: baseOracle,
+                quoteOracle: quoteOracle,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async initializePortfolio(): Promise<string> {
+        return await this.program.methods
+            .initializePortfolio()
+            .accounts({
+                portfolio: getPortfolioAddress(this.wallet.publicKey),
+                user: this.wallet.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async addToPortfolio(
+        marketAddress: PublicKey,
+    ): Promise<string> {
+        return await this.program.methods
+            .addToPortfolio()
+            .accounts({
+                portfolio: getPortfolioAddress(this.wallet.publicKey),
+                user: this.wallet.publicKey,
+                phoenixStrategy: getPhoenixStrategyAddress(this.wallet.publicKey, marketAddress),
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    public async removeFromPortfolio(
+        phoenixStrategy: PublicKey,
+    ): Promise<string> {
+        return await this.program.methods
+            .removeFromPortfolio(phoenixStrategy)
+            .accounts({
+                portfolio: getPortfolioAddress(this.wallet.publicKey),
+                user: this.wallet.publicKey,
+            })
+            .signers([this.wallet])
+            .rpc();
+    }
+
+    // token accounts and fair price overrides are given in portfolio order, overrides can be left empty
+    public async updatePortfolio(
+        tokenAccounts: { quoteAccount: PublicKey, baseAccount: PublicKey }[],
+        fairPriceOverrides: (anchor.BN | null)[] = [],
+        owner: PublicKey = this.wallet.publicKey,
+    ): Promise<string> {
+        let portfolioAddress = getPortfolioAddress(owner);
+        let portfolio = await this.program.account.portfolio.fetch(portfolioAddress);
+        let strategies = portfolio.strategies.slice(0, portfolio.numStrategies.toNumber());
+        let remainingAccounts = [];
+        for (let i = 0; i < strategies.length; i++) {
+            let strategy = await this.program.account.phoenixStrategyState.fetch(strategies[i]);
+            let phoenixClient = await createPhoenixClient(this.connection, strategy.market);
+            const phoenixMarket = phoenixClient.marketStates.get(strategy.market.toBase58());
+            let marketConfigAddress = getMarketConfigAddress(strategy.market);
+            let marketConfig = await this.program.account.marketConfig.fetch(marketConfigAddress);
+            // same order as the update_quotes accounts, followed by the registered oracles
+            // and the history account if the strategy has one
+            let keys = [
+                { pubkey: strategies[i], isWritable: true, isSigner: false },
+                { pubkey: this.wallet.publicKey, isWritable: false, isSigner: true },
+                { pubkey: strategy.trader, isWritable: false, isSigner: strategy.trader.equals(this.wallet.publicKey) },
+                { pubkey: Phoenix.PROGRAM_ID, isWritable: false, isSigner: false },
+                { pubkey: Phoenix.getLogAuthority(), isWritable: false, isSigner: false },
+                { pubkey: strategy.market, isWritable: true, isSigner: false },
+                { pubkey: marketConfigAddress, isWritable: false, isSigner: false },
+                { pubkey: strategy.seat, isWritable: false, isSigner: false },
+                { pubkey: tokenAccounts[i].quoteAccount, isWritable: true, isSigner: false },
+                { pubkey: tokenAccounts[i].baseAccount, isWritable: true, isSigner: false },
+                { pubkey: phoenixMarket.data.header.quoteParams.vaultKey, isWritable: true, isSigner: false },
+                { pubkey: phoenixMarket.data.header.baseParams.vaultKey, isWritable: true, isSigner: false },
+                { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+                { pubkey: marketConfig.baseOracle, isWritable: false, isSigner: false },
+                { pubkey: marketConfig.quoteOracle, isWritable: false, isSigner: false },
+            ];
+            let history = getStrategyHistoryAddress(strategies[i]);
+            if ((await this.connection.getAccountInfo(history)) != null) {
+                keys.push({ pubkey: history, isWritable: true, isSigner: false });
+            }
+            remainingAccounts.push(...keys);
+        }
+        return await this.program.methods
+            .updatePortfolio(fairPriceOverrides)
+            .accounts({
+                portfolio: portfolioAddress,
+            })
+            .remainingAccounts(remainingAccounts)
+            .signers([this.wallet])
+            .rpc();
     }
 
     public async runUberMM(
@@ -113,6 +883,15 @@ export class UberMmSDK {
         const phoenixMarket = phoenixClient.marketStates.get(
             marketAddress.toBase58()
         );
+        let trader = await this.getStrategyTrader(phoenixStrategy);
+
+        let marketConfigAddress = getMarketConfigAddress(marketAddress);
+        let marketConfig = await this.program.account.marketConfig.fetch(marketConfigAddress);
+        // updates are recorded if the strategy has a history account
+        let history = getStrategyHistoryAddress(phoenixStrategy);
+        let historyAccounts = (await this.connection.getAccountInfo(history)) == null
+            ? []
+            : [{ pubkey: history, isWritable: true, isSigner: false }];
 
         let baseDecimals = 10 ** phoenixMarket.data.header.baseParams.decimals;
         let quoteDecimals = 10 ** phoenixMarket.data.header.quoteParams.decimals;
@@ -123,27 +902,22 @@ export class UberMmSDK {
 
         console.log("BaseBalance:", baseStartBalance, "QuoteBalance: ", quoteStartBalance);
         
+        await this.setStrategyParams(marketAddress, params);
 
         for(let i = 0; i < txNumber; i++){
             let timeStart = Date.now();
             try {
                 const tx = await this.program.methods
-                  .updateQuotes(
-                    new BN(0),
-                    new BN(params.quoteEdgeInBps),
-                    new BN(params.quoteSizeInQuoteAtoms),
-                    params.priceImprovementBehavior,
-                    false,
-                    true,
-                    new BN(params.margin),
-                  )
+                  .updateQuotes(null)
                   .accounts({
-                    user: this.wallet.publicKey,
+                    authority: this.wallet.publicKey,
+                    trader: trader,
                     market: marketAddress,
+                    marketConfig: marketConfigAddress,
                     phoenixProgram: Phoenix.PROGRAM_ID,
                     phoenixStrategy: phoenixStrategy,
                     logAuthority: Phoenix.getLogAuthority(),
-                    seat: phoenixMarket.getSeatAddress(this.wallet.publicKey),
+                    seat: phoenixMarket.getSeatAddress(trader),
                     quoteAccount: makerQuoteTokenAccount,
                     baseAccount: makerBaseTokenAccount,
                     quoteVault: phoenixMarket.data.header.quoteParams.vaultKey,
@@ -151,15 +925,16 @@ export class UberMmSDK {
                     tokenProgram: TOKEN_PROGRAM_ID,
                   }).remainingAccounts([
                     {
-                        pubkey: new PublicKey(marketsToPyth[marketAddress.toBase58()][0]),
+                        pubkey: marketConfig.baseOracle,
                         isWritable: false,
                         isSigner: false,
                     },
                     {
-                        pubkey: new PublicKey(marketsToPyth[marketAddress.toBase58()][1]),
+                        pubkey: marketConfig.quoteOracle,
                         isWritable: false,
                         isSigner: false,
-                    }]).signers([this.wallet]).rpc();
+                    },
+                    ...historyAccounts]).signers([this.wallet]).rpc();
                 // let hash = sendAndConfirmTransaction(this.connection, new Transaction().add(tx), [this.wallet]);
                 console.log(i, tx);
                 if(i % 10 == 9){
@@ -172,8 +947,7 @@ export class UberMmSDK {
             let waitTime = Math.max(0, txInterval - timeSpent);
             await new Promise((r) => setTimeout(r, waitTime));
         }
-        let cancelOrdersTx = phoenixMarket.createCancelAllOrdersInstruction(this.wallet.publicKey);
-        console.log("canceling all orders tx: ", await sendAndConfirmTransaction(this.connection, new Transaction().add(cancelOrdersTx), [this.wallet]));
+        console.log("canceling all orders tx: ", await this.cancelAllQuotes(marketAddress));
 
         let getTx = await phoenixMarket.createWithdrawFundsInstruction({withdrawFundsParams:{quoteLotsToWithdraw: null, baseLotsToWithdraw: null}}, this.wallet.publicKey);
         console.log("withdrawing funds tx: ", await sendAndConfirmTransaction(this.connection, new Transaction().add(getTx), [this.wallet]));
@@ -184,18 +958,145 @@ export class UberMmSDK {
         console.log("Balances After MM: ")
         console.log("BaseBalance:", baseBalance, "QuoteBalance: ", quoteBalance);
         
-        let accInfo = await this.connection.getAccountInfo(new PublicKey(marketsToPyth[marketAddress.toBase58()][0]));
+        let accInfo = await this.connection.getAccountInfo(marketConfig.baseOracle);
         let priceBase = parsePriceData(accInfo.data).aggregate.price;
-        accInfo = await this.connection.getAccountInfo(new PublicKey(marketsToPyth[marketAddress.toBase58()][1]));
+        accInfo = await this.connection.getAccountInfo(marketConfig.quoteOracle);
         let priceQuote = parsePriceData(accInfo.data).aggregate.price;
         console.log("Profit Made: ", baseBalance * priceBase - baseStartBalance * priceBase + quoteBalance * priceQuote - quoteStartBalance * priceQuote, "USD");
+        let pnl = await this.getPnl(marketAddress);
+        console.log(
+            "On-chain PnL: realized",
+            pnl.realizedPnlInQuoteAtoms.toNumber() / quoteDecimals,
+            "unrealized",
+            pnl.unrealizedPnlInQuoteAtoms.toNumber() / quoteDecimals,
+        );
     }
 }
 
+export function getPhoenixStrategyAddress(trader: PublicKey, marketAddress: PublicKey): PublicKey {
+    let [phoenixStrategy, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("phoenix"),
+            trader.toBuffer(),
+            marketAddress.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return phoenixStrategy;
+}
+
+// seeds are "vault", "shares", "quote" or "base"
+export function getStrategyVaultAddress(seed: string, phoenixStrategy: PublicKey): PublicKey {
+    let [address, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode(seed),
+            phoenixStrategy.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return address;
+}
+
+export function getWithdrawalRequestAddress(vault: PublicKey, lp: PublicKey): PublicKey {
+    let [withdrawalRequest, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("withdrawal"),
+            vault.toBuffer(),
+            lp.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return withdrawalRequest;
+}
+
+export function getStrategyHistoryAddress(phoenixStrategy: PublicKey): PublicKey {
+    let [history, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("history"),
+            phoenixStrategy.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return history;
+}
+
+export function getGlobalConfigAddress(): PublicKey {
+    let [config, _] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("config")],
+        UBER_MM_PROGRAM_ID
+    );
+    return config;
+}
+
+// registry entry holding the oracles of a Phoenix market
+export function getMarketConfigAddress(marketAddress: PublicKey): PublicKey {
+    let [marketConfig, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("market"),
+            marketAddress.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return marketConfig;
+}
+
+export function getPortfolioAddress(owner: PublicKey): PublicKey {
+    let [portfolio, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("portfolio"),
+            owner.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return portfolio;
+}
+
+// program derived key that trades on Phoenix for program owned strategies
+export function getTraderAuthorityAddress(phoenixStrategy: PublicKey): PublicKey {
+    let [traderAuthority, _] = PublicKey.findProgramAddressSync(
+        [
+            anchor.utils.bytes.utf8.encode("trader"),
+            phoenixStrategy.toBuffer(),
+        ],
+        UBER_MM_PROGRAM_ID
+    );
+    return traderAuthority;
+}
+
+const priceImprovementBehaviors = [
+    { ubermensch: {} },
+    { join: {} },
+    { dime: {} },
+    { ignore: {} },
+];
+
+function optionalBN(value?: number | null) {
+    return value != undefined ? new BN(value) : null;
+}
+
 interface MMParams {
     quoteEdgeInBps: number; // edge from fair price in which we put orders
     quoteSizeInQuoteAtoms: number; // size of orders in quote atoms
-    postOnly: boolean;
+    postOnly: boolean; // limit orders using selfTradeBehavior otherwise
     priceImprovementBehavior: number; // 0 = ubermensch, 1 = join, 2 = dime, 3 = ignore
     margin: number; // minimum quote edge accepted(only used in ubermensch mode)
+}
+
+interface RiskParams {
+    maxBookDeviationInBps?: number; // pull quotes when the book is further than this from the oracle, 0 disables
+    minSlotsBetweenUpdates?: number; // updates sent sooner than this are rejected, 0 disables
+    maxOrderNotionalInQuoteAtoms?: number; // orders larger than this are rejected, 0 disables
+    maxCancelReplaceCycles?: number; // cancel-replace cycles allowed per window, 0 disables
+    cancelReplaceWindowInSlots?: number; // length of the cancel-replace window
+    minQuotePriceInTicks?: number; // lowest price we are allowed to quote at, 0 disables
+    maxQuotePriceInTicks?: number; // highest price we are allowed to quote at, 0 disables
+    heartbeatInSlots?: number; // slots without an update after which anyone can cancel our quotes, 0 disables
+}
+
+interface ExecutionParams {
+    selfTradeBehavior?: { abort: {} } | { cancelProvide: {} } | { decrementTake: {} }; // applies to limit orders
+    failSilentlyOnInsufficientFunds?: boolean; // skip an underfunded side instead of failing the whole transaction
+    quoteReserveInQuoteAtoms?: number; // quote kept out of orders
+    baseReserveInBaseAtoms?: number; // base kept out of orders
+    minOrderSizeInBaseLots?: number; // sides that can't be funded for this size are skipped
 }
\ No newline at end of file